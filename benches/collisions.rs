@@ -0,0 +1,105 @@
+//! Compares the naive collision detection, which checks every projectile against every enemy, with the spatial hash broadphase.
+//!
+//! Run with `cargo bench --bench collisions`.
+
+use std::time::{Duration, Instant};
+
+use rand::{Rng, SeedableRng};
+use wasm_asteroids::{
+    enemy::Enemy,
+    game::GameRng,
+    game_settings::GameSettings,
+    positioned::Positioned,
+    projectile::{Faction, Projectile},
+    spatial_hash::SpatialHash,
+};
+
+const ITERATIONS: u32 = 200;
+
+/// Creates enemies and projectiles spread over the whole field.
+fn objects(enemies: usize, projectiles: usize) -> (GameSettings, Vec<Enemy>, Vec<Projectile>) {
+    let settings = GameSettings::new();
+    let mut rng = GameRng::seed_from_u64(0);
+    let (width, height) = (settings.field.width as f32, settings.field.height as f32);
+
+    let enemies = (0..enemies)
+        .map(|id| {
+            let mut enemy = Enemy::new(&settings, 0.0, id as i64, &mut rng);
+            // Split asteroids are small
+            enemy.radius = rng.gen_range(5.0..20.0);
+            enemy.set_position(rng.gen_range(0.0..width), rng.gen_range(0.0..height));
+            enemy
+        })
+        .collect();
+    let projectiles = (0..projectiles)
+        .map(|id| Projectile {
+            id: id as i64,
+            dx: 0.0,
+            dy: 0.0,
+            x: rng.gen_range(0.0..width),
+            y: rng.gen_range(0.0..height),
+            hits: 1,
+            damage: 1,
+            radius: 20.0,
+            lifetime: 2.0,
+            faction: Faction::Player,
+        })
+        .collect();
+    (settings, enemies, projectiles)
+}
+
+/// Counts the colliding pairs by checking every enemy against every projectile.
+fn naive(settings: &GameSettings, enemies: &[Enemy], projectiles: &[Projectile]) -> usize {
+    enemies
+        .iter()
+        .filter(|enemy| {
+            projectiles
+                .iter()
+                .any(|p| enemy.is_collision(p, &settings.field))
+        })
+        .count()
+}
+
+/// Counts the colliding pairs using the spatial hash.
+fn broadphase(settings: &GameSettings, enemies: &[Enemy], projectiles: &[Projectile]) -> usize {
+    let grid = SpatialHash::from_objects(&settings.field, projectiles);
+    let mut candidates = vec![];
+    enemies
+        .iter()
+        .filter(|enemy| {
+            grid.query_into(*enemy, &mut candidates);
+            candidates
+                .iter()
+                .any(|&i| enemy.is_collision(&projectiles[i], &settings.field))
+        })
+        .count()
+}
+
+fn measure(f: impl Fn() -> usize) -> (usize, Duration) {
+    let start = Instant::now();
+    let mut result = 0;
+    for _ in 0..ITERATIONS {
+        result = std::hint::black_box(f());
+    }
+    (result, start.elapsed() / ITERATIONS)
+}
+
+fn main() {
+    println!("enemies projectiles       naive  broadphase  speedup");
+    for (enemies, projectiles) in [(20, 5), (100, 20), (500, 50), (1000, 100), (2000, 200)] {
+        let (settings, enemies, projectiles) = objects(enemies, projectiles);
+
+        let (naive_hits, naive_time) = measure(|| naive(&settings, &enemies, &projectiles));
+        let (grid_hits, grid_time) = measure(|| broadphase(&settings, &enemies, &projectiles));
+        assert_eq!(naive_hits, grid_hits);
+
+        println!(
+            "{:>7} {:>11} {:>11.1?} {:>11.1?} {:>7.1}x",
+            enemies.len(),
+            projectiles.len(),
+            naive_time,
+            grid_time,
+            naive_time.as_secs_f64() / grid_time.as_secs_f64()
+        );
+    }
+}
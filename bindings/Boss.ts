@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WeakPoint } from "./WeakPoint";
+
+export interface Boss { id: bigint, x: number, y: number, dx: number, dy: number, radius: number, health: number, max_health: number, angle: number, time_until_attack: number, weak_points: Array<WeakPoint>, }
\ No newline at end of file
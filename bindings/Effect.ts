@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PowerUpKind } from "./PowerUpKind";
+
+export interface Effect { kind: PowerUpKind, time_left: number, amount: number, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PowerUpKind } from "./PowerUpKind";
+
+export type GameEvent = { "type": "EnemyDestroyed", id: bigint, enemy_type: string, x: number, y: number, } | { "type": "EnemySplit", id: bigint, enemy_type: string, x: number, y: number, children: Array<bigint>, } | { "type": "EnemyDamaged", id: bigint, x: number, y: number, health: number, armor: number, } | { "type": "EnemySwallowed", id: bigint, x: number, y: number, } | { "type": "GravityWellSpawned", id: bigint, x: number, y: number, } | { "type": "ProjectileFired", id: bigint, faction: string, } | { "type": "BossSpawned", id: bigint, max_health: number, } | { "type": "BossPhaseChanged", id: bigint, phase: number, } | { "type": "BossDefeated", id: bigint, x: number, y: number, coins: number, } | { "type": "WaveStarted", number: number, } | { "type": "WaveCleared", number: number, } | { "type": "PowerUpDropped", id: bigint, kind: PowerUpKind, x: number, y: number, } | { "type": "PowerUpCollected", id: bigint, kind: PowerUpKind, } | { "type": "PowerUpExpired", kind: PowerUpKind, } | { "type": "ShipHit", lives: number, } | { "type": "CoinsGained", amount: number, } | { "type": "ItemBought", item_id: number, level: number, } | { "type": "GameOver", score: number, };
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface WeakPoint { x: number, y: number, radius: number, offset_angle: number, }
\ No newline at end of file
@@ -0,0 +1,276 @@
+//! A headless simulation of the game.
+//!
+//! Runs a [Game](wasm_asteroids::game::Game) natively for a number of ticks, optionally driven by a scripted input file, and prints the result as json.
+//! This is used for balance experiments and regression checks outside of the browser.
+//!
+//! ```text
+//! asteroids-sim --ticks <N> [--seed <SEED>] [--input <FILE>] [--toroidal] [--ship-movement] [--stats-interval <K>]
+//! asteroids-sim --replay <FILE> [--verify <CLAIM>] [--stats-interval <K>]
+//! ```
+//!
+//! The input file contains a json array of `[tick, action]` pairs, e.g. `[[0, {"RotateShip": [1.0, 0.0]}], [10, "Shoot"]]`.
+//! The action is applied after `tick` ticks were simulated. The game is started before the first tick.
+//! With `--toroidal` the game is played on a field that wraps around at its edges. With `--ship-movement` the ship can fly
+//! around the field using `{"Thrust": true}` inputs.
+//!
+//! Instead of scripted inputs, a replay recorded by the game can be simulated. With `--verify` the replay is checked against a
+//! submitted game ressource instead: the verification report is printed and the exit code is 1 if the claim isn't reachable.
+
+use std::{env, fs, process};
+
+use serde::Serialize;
+use wasm_asteroids::{
+    game::{random_seed, GameAction, GameState},
+    game_ressources::GameRessource,
+    game_settings::field::FieldTopology,
+    replay::{Replay, ReplayPlayer, REPLAY_VERSION},
+    verification::verify,
+};
+
+const USAGE: &str =
+    "Usage: asteroids-sim --ticks <N> [--seed <SEED>] [--input <FILE>] [--toroidal] [--ship-movement]
+                     [--stats-interval <K>]
+       asteroids-sim --replay <FILE> [--verify <CLAIM>] [--stats-interval <K>]";
+
+/// The options of a simulation run.
+struct Options {
+    /// The number of ticks to simulate. Defaults to the length of the replay.
+    ticks: Option<u32>,
+    /// The seed of the game. A random seed is used if none is given.
+    seed: Option<u64>,
+    /// The path to the scripted input file
+    input: Option<String>,
+    /// Whether the field wraps around at its edges
+    toroidal: bool,
+    /// Whether the ship can move freely
+    ship_movement: bool,
+    /// The path to a recorded replay
+    replay: Option<String>,
+    /// The path to a game ressource that is verified against the replay
+    verify: Option<String>,
+    /// Every how many ticks the stats are recorded
+    stats_interval: u32,
+}
+
+/// The stats of the game after a tick.
+#[derive(Serialize)]
+struct TickStats {
+    tick: u32,
+    score: f64,
+    coins: i32,
+    lives: i32,
+    enemies: usize,
+    projectiles: usize,
+}
+
+/// The level of a shop item at the end of the simulation.
+#[derive(Serialize)]
+struct ItemLevel {
+    id: u32,
+    title: &'static str,
+    level: u8,
+}
+
+/// The result of a simulation run.
+#[derive(Serialize)]
+struct SimulationResult {
+    seed: u64,
+    ticks: u32,
+    state: GameState,
+    score: f64,
+    coins: i32,
+    lives: i32,
+    items: Vec<ItemLevel>,
+    stats: Vec<TickStats>,
+}
+
+fn main() {
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|e| exit_with_error(&e));
+
+    let mut replay = match &options.replay {
+        Some(path) => read_replay(path),
+        None => {
+            let inputs = match &options.input {
+                Some(path) => read_json(path).unwrap_or_else(|e| exit_with_error(&e)),
+                None => vec![],
+            };
+            scripted_replay(&options, inputs)
+        }
+    }
+    .unwrap_or_else(|e| exit_with_error(&e));
+    if let Some(ticks) = options.ticks {
+        replay.ticks = ticks;
+    }
+
+    if let Some(path) = &options.verify {
+        let claim = read_json(path)
+            .and_then(|save| {
+                GameRessource::from_json(save)
+                    .map_err(|e| format!("Invalid game ressource {}: {:?}", path, e))
+            })
+            .unwrap_or_else(|e| exit_with_error(&e));
+        let report =
+            verify(&replay, &claim).unwrap_or_else(|e| exit_with_error(&format!("{:?}", e)));
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("The report is always serializable")
+        );
+        process::exit(if report.valid { 0 } else { 1 });
+    }
+
+    let result = simulate(replay, options.stats_interval);
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&result).expect("The result is always serializable")
+    );
+}
+
+/// Parses the command line arguments.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut ticks = None;
+    let mut seed = None;
+    let mut input = None;
+    let mut toroidal = false;
+    let mut ship_movement = false;
+    let mut replay = None;
+    let mut verify = None;
+    let mut stats_interval = 1;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("Missing value for {}", name));
+        match arg.as_str() {
+            "--ticks" => ticks = Some(parse_number(&value("--ticks")?)?),
+            "--seed" => seed = Some(parse_number(&value("--seed")?)?),
+            "--input" => input = Some(value("--input")?),
+            "--toroidal" => toroidal = true,
+            "--ship-movement" => ship_movement = true,
+            "--replay" => replay = Some(value("--replay")?),
+            "--verify" => verify = Some(value("--verify")?),
+            "--stats-interval" => stats_interval = parse_number(&value("--stats-interval")?)?,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    if replay.is_some() && (seed.is_some() || input.is_some() || toroidal || ship_movement) {
+        return Err(
+            "--replay can't be combined with --seed, --input, --toroidal or --ship-movement"
+                .to_string(),
+        );
+    }
+    if replay.is_none() && (ticks.is_none() || verify.is_some()) {
+        return Err(
+            "Either --replay or --ticks is required, --verify requires --replay".to_string(),
+        );
+    }
+
+    Ok(Options {
+        ticks,
+        seed,
+        input,
+        toroidal,
+        ship_movement,
+        replay,
+        verify,
+        stats_interval: stats_interval.max(1),
+    })
+}
+
+/// Parses a number argument.
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} is not a valid number", value))
+}
+
+/// Reads a json file.
+fn read_json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid file {}: {}", path, e))
+}
+
+/// Reads a recorded replay from a file.
+fn read_replay(path: &str) -> Result<Replay, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+    let replay =
+        Replay::from_json(&content).map_err(|e| format!("Invalid replay {}: {}", path, e))?;
+    replay
+        .check_version()
+        .map_err(|e| format!("Unsupported replay {}: {:?}", path, e))?;
+    Ok(replay)
+}
+
+/// Creates the replay that drives a simulation with scripted inputs.
+fn scripted_replay(
+    options: &Options,
+    mut inputs: Vec<(u32, GameAction)>,
+) -> Result<Replay, String> {
+    let seed = options.seed.unwrap_or_else(random_seed);
+
+    // The replay applies the inputs in order, so the scripted inputs have to be sorted by their tick
+    inputs.sort_by_key(|(tick, _)| *tick);
+    inputs.insert(0, (0, GameAction::Start));
+    Ok(Replay {
+        version: REPLAY_VERSION,
+        seed,
+        topology: if options.toroidal {
+            FieldTopology::Toroidal
+        } else {
+            FieldTopology::Bounded
+        },
+        ship_movement: options.ship_movement,
+        ticks: options.ticks.ok_or("Missing --ticks")?,
+        inputs,
+    })
+}
+
+/// Runs the simulation and collects the stats.
+fn simulate(replay: Replay, stats_interval: u32) -> SimulationResult {
+    let (seed, ticks) = (replay.seed, replay.ticks);
+    let mut game = replay.new_game();
+    let mut player = ReplayPlayer::new(replay);
+    let mut stats = vec![];
+    let mut tick = 0;
+    while player.step(&mut game) {
+        tick += 1;
+        if tick % stats_interval == 0 {
+            stats.push(TickStats {
+                tick,
+                score: game.score,
+                coins: game.coins,
+                lives: game.lives,
+                enemies: game.enemies.len(),
+                projectiles: game.projectiles.len(),
+            });
+        }
+    }
+
+    SimulationResult {
+        seed,
+        ticks,
+        state: game.state,
+        score: game.score,
+        coins: game.coins,
+        lives: game.lives,
+        items: game
+            .shop
+            .items
+            .iter()
+            .map(|item| ItemLevel {
+                id: item.id,
+                title: item.title,
+                level: item.level,
+            })
+            .collect(),
+        stats,
+    }
+}
+
+/// Prints the error and the usage and exits.
+fn exit_with_error(error: &str) -> ! {
+    eprintln!("{}\n{}", error, USAGE);
+    process::exit(2);
+}
@@ -3,13 +3,10 @@
 //!
 //! The [GameModel] is the entry point of the game. This model creates the game and manages it by e.g. recreating it when the player wants to start a new one. It also contains the error messages displayed to the user.
 //!
-//! The [Game](Game) struct is the heart of this model. It contains the logic of the central `tick` method used to calculate the next frame, holds all objects of the game and orchestrates their interaction. It also contains the [GameSettings](GameSettings) which are used by the shop system to apply the item functions.
+//! The [Game](game::Game) struct is the heart of this model. It contains the logic of the central `tick` method used to calculate the next frame, holds all objects of the game and orchestrates their interaction. It also contains the [GameSettings](game_settings::GameSettings) which are used by the shop system to apply the item functions.
 use wasm_bindgen::prelude::*;
 
 mod model;
-// Those imports are used in the doc-block above.
-use model::game::Game;
-use model::game_settings::GameSettings;
 pub use model::*;
 
 // When the `wee_alloc` feature is enabled, this uses `wee_alloc` as the global
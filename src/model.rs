@@ -1,25 +1,49 @@
+pub mod archetype;
+pub mod boss;
+pub mod collider;
 pub mod enemy;
 pub mod error_message;
 pub mod game;
+pub mod game_event;
 pub mod game_ressources;
 pub mod game_settings;
+pub mod gravity_well;
+pub mod id_generator;
 pub mod positioned;
+pub mod power_up;
 pub mod projectile;
+pub mod replay;
 pub mod shop;
+pub mod snapshot;
+pub mod spatial_hash;
+pub mod steering;
+pub mod verification;
+pub mod wave;
 
 use shop::Item;
 use wasm_bindgen::prelude::*;
 
-use game::Game;
 use game::GameAction;
+use game::{random_seed, Game};
+use game::{TICKS_PER_SECOND, TICK_DURATION};
+use power_up::Effect;
 
-use crate::game_ressources::GameRessource;
-use crate::model::game_settings::field::Field;
+use crate::error;
+use crate::game_ressources::{GameRessource, LoadError, SAVE_VERSION};
+use crate::model::game_settings::field::{Field, FieldTopology};
 use crate::model::game_settings::ship::Ship;
 
 use crate::model::game::GameState;
 
 use self::error_message::ErrorMessage;
+use self::replay::{Replay, ReplayPlayer};
+use self::snapshot::GameSnapshot;
+
+/// The longest time in milliseconds a single call to `advance` catches up on. Longer gaps (e.g. while the tab was in the background) are dropped, so the game doesn't freeze calculating hundreds of ticks.
+const MAX_FRAME_TIME: f64 = 250.0;
+
+/// The duration of a tick in milliseconds.
+const TICK_DURATION_MS: f64 = 1000.0 / TICKS_PER_SECOND as f64;
 
 /// The model of the game. It contains all the information about the game state.
 #[wasm_bindgen]
@@ -28,30 +52,112 @@ pub struct GameModel {
     game: Game,
     /// The error message
     error_message: ErrorMessage,
+    /// Whether new games should be recorded
+    record_replays: bool,
+    /// The recording of the current game. This is only set if recording is enabled.
+    replay: Option<Replay>,
+    /// The replay that is currently played back. While a replay is played, the inputs of the player are ignored.
+    replay_player: Option<ReplayPlayer>,
+    /// The number of frames that can be rewound
+    rewind_depth: usize,
+    /// The topology of the field of new games
+    field_topology: FieldTopology,
+    /// Whether the ship can move freely in new games
+    ship_movement: bool,
+    /// The time in milliseconds that has passed in the frontend but not yet in the game
+    accumulator: f64,
+}
+
+impl Default for GameModel {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[wasm_bindgen]
 impl GameModel {
     /// Creates a new game model
     pub fn new() -> Self {
+        Self::from_game(Game::new())
+    }
+
+    /// Creates a new game model whose game is seeded with the given seed.
+    pub fn new_with_seed(seed: u64) -> Self {
+        Self::from_game(Game::new_with_seed(seed))
+    }
+
+    /// Internal function to create a game model around a game.
+    fn from_game(game: Game) -> Self {
         Self {
-            game: Game::new(),
+            game,
             error_message: ErrorMessage::new_empty(),
+            record_replays: false,
+            replay: None,
+            replay_player: None,
+            rewind_depth: 0,
+            field_topology: FieldTopology::Bounded,
+            ship_movement: false,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Internal function to replace the current game. The new game keeps the rewind depth of the model.
+    fn set_game(&mut self, mut game: Game) {
+        game.set_history_depth(self.rewind_depth);
+        self.game = game;
+    }
+
+    /// Advances the game by the time in milliseconds that passed since the last call.
+    ///
+    /// The game is always calculated in [ticks](GameModel::tick) of the same duration, so it runs at the same speed on every screen. Time that is left over is carried over to the next call.
+    pub fn advance(&mut self, elapsed_ms: f64) {
+        self.accumulator += elapsed_ms.clamp(0.0, MAX_FRAME_TIME);
+        while self.accumulator >= TICK_DURATION_MS {
+            self.tick();
+            self.accumulator -= TICK_DURATION_MS;
         }
     }
 
     /// Calculates the next frame of the game.
     pub fn tick(&mut self) {
         self.error_message.tick();
+        if let Some(player) = &mut self.replay_player {
+            if !player.step(&mut self.game) {
+                // Continue recording where the replay ended
+                self.replay = self.record_replays.then(|| player.replay().clone());
+                self.replay_player = None;
+            }
+            return;
+        }
         self.take_game_action(GameAction::Tick)
     }
 
     /// Starts a new game.
     pub fn start_game(&mut self) {
-        self.game = Game::new();
+        self.start_game_with_seed(random_seed());
+    }
+
+    /// Starts a new game with the given seed. Two games started with the same seed play out the same way for the same inputs.
+    pub fn start_game_with_seed(&mut self, seed: u64) {
+        let mut replay = Replay::new(seed);
+        replay.topology = self.field_topology;
+        replay.ship_movement = self.ship_movement;
+        self.set_game(replay.new_game());
+        self.replay_player = None;
+        self.replay = self.record_replays.then_some(replay);
         self.resume_game();
     }
 
+    /// Sets the topology of the field for new games. On a toroidal field objects re-enter the field on the opposite edge.
+    pub fn set_field_topology(&mut self, topology: FieldTopology) {
+        self.field_topology = topology;
+    }
+
+    /// Sets whether the ship can move freely in new games. Otherwise it stays in the middle of the field.
+    pub fn set_ship_movement(&mut self, ship_movement: bool) {
+        self.ship_movement = ship_movement;
+    }
+
     /// Ends the game.
     pub fn end_game(&mut self) {
         self.take_game_action(GameAction::End);
@@ -67,6 +173,11 @@ impl GameModel {
         self.take_game_action(GameAction::RotateShip(dx, dy))
     }
 
+    /// Starts or stops the engine of the ship. This only has an effect if the ship can move freely.
+    pub fn thrust(&mut self, thrusting: bool) {
+        self.take_game_action(GameAction::Thrust(thrusting))
+    }
+
     /// Pauses the game.
     pub fn pause_game(&mut self) {
         self.take_game_action(GameAction::Pause)
@@ -83,7 +194,15 @@ impl GameModel {
     }
 
     /// Internal function to handle potential errors returned by the game.
+    ///
+    /// The action is recorded if recording is enabled. While a replay is played back, the action is ignored.
     fn take_game_action(&mut self, game_action: GameAction) {
+        if self.replay_player.is_some() {
+            return;
+        }
+        if let Some(replay) = &mut self.replay {
+            replay.record(game_action);
+        }
         if let Err(e) = self.game.step(game_action) {
             self.error_message = e;
         }
@@ -94,10 +213,121 @@ impl GameModel {
         self.error_message.dismiss();
     }
 
-    /// Loads a game from a game ressource.
-    pub fn load_game(&mut self, game_ressource: JsValue) {
-        let game_ressource: GameRessource = serde_wasm_bindgen::from_value(game_ressource).unwrap();
-        self.game = Game::load(game_ressource);
+    /// Loads a game from a game ressource. Older saves are upgraded to the current save format first.
+    ///
+    /// Returns false and shows an error message if the save is malformed or invalid. The current game is kept in that case.
+    pub fn load_game(&mut self, game_ressource: JsValue) -> bool {
+        match Self::parse_game(game_ressource) {
+            Ok(game) => {
+                self.set_game(game);
+                // A loaded game can't be reproduced from a seed
+                self.replay = None;
+                self.replay_player = None;
+                true
+            }
+            Err(e) => {
+                self.error_message = e.into();
+                false
+            }
+        }
+    }
+
+    /// Internal function to parse, migrate and validate a game ressource.
+    fn parse_game(game_ressource: JsValue) -> Result<Game, LoadError> {
+        let save: serde_json::Value =
+            serde_wasm_bindgen::from_value(game_ressource).map_err(|_| LoadError::Malformed)?;
+        Game::load(GameRessource::from_json(save)?)
+    }
+
+    /// Returns a snapshot of the complete game state as json. This is saved together with the game ressource.
+    pub fn get_snapshot(&self) -> String {
+        self.game.snapshot().to_json()
+    }
+
+    /// Restores the game from a snapshot created by `get_snapshot`.
+    ///
+    /// Returns false and shows an error message if the snapshot is malformed or doesn't describe a reachable game. The current game is kept in that case.
+    pub fn load_snapshot(&mut self, snapshot: &str) -> bool {
+        let snapshot = match GameSnapshot::from_json(snapshot) {
+            Ok(snapshot) => snapshot,
+            Err(_) => {
+                self.error_message = error!("Invalid snapshot");
+                return false;
+            }
+        };
+        match snapshot.validate() {
+            Ok(()) => {
+                self.set_game(Game::from_snapshot(snapshot));
+                self.replay = None;
+                self.replay_player = None;
+                true
+            }
+            Err(e) => {
+                self.error_message = e.into();
+                false
+            }
+        }
+    }
+
+    /// Sets the number of frames that are kept to rewind the game. 0 disables rewinding.
+    pub fn set_rewind_depth(&mut self, depth: usize) {
+        self.rewind_depth = depth;
+        self.game.set_history_depth(depth);
+    }
+
+    /// Rewinds the game by up to the given number of frames and pauses it. Returns the number of frames that were actually rewound.
+    ///
+    /// The recording of the game is stopped, as it no longer describes the run.
+    pub fn rewind(&mut self, ticks: usize) -> usize {
+        if self.replay_player.is_some() {
+            return 0;
+        }
+        let rewound = self.game.rewind(ticks);
+        if rewound > 0 {
+            self.replay = None;
+        }
+        rewound
+    }
+
+    /// Steps forward to the next rewound frame. Returns false if there is no rewound frame left.
+    pub fn step_forward(&mut self) -> bool {
+        self.replay_player.is_none() && self.game.step_forward()
+    }
+
+    /// Enables or disables the recording of replays. This takes effect when the next game is started.
+    pub fn set_record_replays(&mut self, record_replays: bool) {
+        self.record_replays = record_replays;
+    }
+
+    /// Returns the recording of the current game as json if it is recorded.
+    pub fn get_replay(&self) -> Option<String> {
+        self.replay.as_ref().map(Replay::to_json)
+    }
+
+    /// Loads a replay and plays it back. Every call to `tick` advances the replay by one frame until it is over.
+    pub fn load_replay(&mut self, replay: &str) {
+        match Replay::from_json(replay) {
+            Ok(replay) => {
+                if let Err(e) = replay.check_version() {
+                    self.error_message = e.into();
+                    return;
+                }
+                self.set_game(replay.new_game());
+                self.replay = None;
+                self.replay_player = Some(ReplayPlayer::new(replay));
+            }
+            Err(_) => self.error_message = error!("Invalid replay"),
+        }
+    }
+
+    /// Stops playing back a replay. The game continues from the current frame and takes inputs again, but it is no longer recorded.
+    pub fn stop_replay(&mut self) {
+        self.replay_player = None;
+    }
+
+    /// Returns whether a replay is currently played back.
+    pub fn is_replaying(&self) -> bool {
+        self.replay_player.is_some()
     }
 
     /// The id is optional. The id is determined by the api. This function is used to set the id after the game is saved to the api.
@@ -107,11 +337,21 @@ impl GameModel {
 
     // Getter
 
+    /// Returns the version of the save format. This is saved together with the game ressource.
+    pub fn get_save_version(&self) -> u32 {
+        SAVE_VERSION
+    }
+
     /// Returns the id of the game.
     pub fn get_id(&self) -> Option<u32> {
         self.game.id
     }
 
+    /// Returns the seed of the current game.
+    pub fn get_seed(&self) -> u64 {
+        self.game.seed
+    }
+
     /// Return the enemy spawn timeout. This is used to save the game.
     pub fn get_enemy_spawn_timeout(&self) -> f32 {
         self.game.game_settings.enemy_spawn_timeout
@@ -147,6 +387,67 @@ impl GameModel {
         serde_wasm_bindgen::to_value(&self.game.projectiles).unwrap()
     }
 
+    /// Returns the gravity wells. See `get_enemies` for more information.
+    pub fn get_gravity_wells(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.game.gravity_wells).unwrap()
+    }
+
+    /// Returns the power-ups on the field. See `get_enemies` for more information.
+    pub fn get_power_ups(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.game.power_ups).unwrap()
+    }
+
+    /// Returns the active effects of collected power-ups with the time they have left. See `get_enemies` for more information.
+    pub fn get_effects(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.game.effects).unwrap()
+    }
+
+    /// Returns if the ship is protected by the shield of a power-up.
+    pub fn is_shielded(&self) -> bool {
+        self.game.game_settings.ship_shielded
+    }
+
+    /// Returns the number of the current wave, starting with 1.
+    pub fn get_wave_number(&self) -> u32 {
+        self.game.wave.number
+    }
+
+    /// Returns the fraction of the enemies of the current wave that were spawned so far, between 0 and 1.
+    pub fn get_wave_progress(&self) -> f32 {
+        self.game.wave.progress()
+    }
+
+    /// Returns the number of enemies of the current wave that are still to come or on the field.
+    pub fn get_wave_enemies_left(&self) -> u32 {
+        self.game.wave.total() - self.game.wave.spawned.min(self.game.wave.total())
+            + self.game.enemies.len() as u32
+    }
+
+    /// Returns the time in seconds until the next wave starts. This is only counted down during the intermission.
+    pub fn get_time_until_next_wave(&self) -> f32 {
+        self.game.time_until_next_wave.max(0.0)
+    }
+
+    /// Returns the boss, or undefined if there is no boss on the field. See `get_enemies` for more information.
+    pub fn get_boss(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.game.boss).unwrap()
+    }
+
+    /// Returns the phase of the boss, or undefined if there is no boss on the field.
+    pub fn get_boss_phase(&self) -> Option<u8> {
+        self.game.boss.as_ref().map(|boss| boss.phase())
+    }
+
+    /// Returns the health the boss has left as a fraction between 0 and 1, or undefined if there is no boss on the field. This is used for the health bar of the boss.
+    pub fn get_boss_health_fraction(&self) -> Option<f32> {
+        self.game.boss.as_ref().map(|boss| boss.health_fraction())
+    }
+
+    /// Returns the events that happened since the last call, e.g. destroyed enemies or bought items. See `get_enemies` for more information.
+    pub fn take_events(&mut self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.game.take_events()).unwrap()
+    }
+
     /// Returns the field.
     pub fn get_field(&self) -> Field {
         self.game.game_settings.field
@@ -169,9 +470,23 @@ impl GameModel {
 
     /// Return the percentage to which the time until the next shot has run out.
     pub fn get_shoot_refill_percentage(&self) -> i32 {
-        100 - ((self.game.time_until_next_shot as f32
-            / self.game.game_settings.shoot_timeout as f32) as f32
-            * 100.0) as i32
+        let timeout = Effect::shoot_timeout(&self.game.effects, &self.game.game_settings);
+        100 - ((self.game.time_until_next_shot / timeout) * 100.0) as i32
+    }
+
+    /// Returns how far the frontend is between the last tick and the next one, between 0 and 1.
+    ///
+    /// The objects are drawn this far along their last move, so they move smoothly on screens with a higher frame rate than the tick rate. While the game is not running, objects are drawn where they are.
+    pub fn get_interpolation_alpha(&self) -> f64 {
+        if self.game.state != GameState::Running {
+            return 1.0;
+        }
+        self.accumulator / TICK_DURATION_MS
+    }
+
+    /// Returns the duration of a tick in seconds.
+    pub fn get_tick_duration(&self) -> f32 {
+        TICK_DURATION
     }
 
     /// Returns if the ship is currently invulnerable.
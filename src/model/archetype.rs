@@ -0,0 +1,214 @@
+//! Enemy archetypes describe the kinds of enemies in the game.
+//!
+//! The archetypes are not part of the code. They are read from the catalog in `archetypes.json`, which is bundled with the game, so new kinds of enemies can be added by editing it.
+//! Every enemy refers to its archetype by [name](Archetype::name).
+
+use once_cell::sync::OnceCell;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// The catalog of archetypes that is bundled with the game.
+const CATALOG: &str = include_str!("archetypes.json");
+
+/// How enemies of an archetype act.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum ArchetypeBehaviour {
+    /// The enemies fly in a straight line
+    #[default]
+    Drift,
+    /// The enemies [steer](crate::steering::steer) towards the ship and shoot at it
+    Hunt,
+    /// The enemies [flock](crate::steering::flock) together with the enemies of their swarm and are drawn towards the ship
+    Swarm,
+}
+
+/// How likely an enemy of an archetype spawns. The weight changes linearly from `start` to `end` until the score reaches `score`.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Debug)]
+pub struct SpawnWeight {
+    /// The weight at the start of the game
+    pub start: f32,
+    /// The weight from `score` on
+    pub end: f32,
+    /// The score at which the weight reaches `end`
+    pub score: f64,
+}
+
+impl SpawnWeight {
+    /// Returns the weight at the given score.
+    pub fn at(&self, score: f64) -> f32 {
+        let progress = if self.score > 0.0 {
+            (score / self.score).clamp(0.0, 1.0) as f32
+        } else {
+            1.0
+        };
+        self.start + (self.end - self.start) * progress
+    }
+}
+
+/// How enemies of an archetype split when they are destroyed.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct SplitRule {
+    /// The range of the radius below that an enemy doesn't split. Every enemy rolls its own limit
+    pub reproduce_limit: (f32, f32),
+    /// The smallest and largest number of children
+    pub children: (u32, u32),
+    /// The archetype of the children. If this is none the children have the archetype of their parent
+    pub child: Option<String>,
+}
+
+/// The reasons a catalog of archetypes can't be used.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CatalogError {
+    /// The catalog has no archetypes, so no enemy can spawn
+    Empty,
+    /// There are several archetypes with the given name
+    DuplicateName(String),
+    /// The archetype with the given name splits into an archetype that isn't in the catalog
+    UnknownChild(String),
+    /// The archetype with the given name has a range whose minimum is above its maximum
+    UnorderedRange(String),
+    /// A [wave](crate::wave::Wave) spawns enemies of the given archetype, which isn't in the catalog
+    UnknownWaveArchetype(String),
+}
+
+/// A kind of enemy.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Archetype {
+    /// The unique name of the archetype. The frontend draws enemies by this name
+    pub name: String,
+    /// The range of the radius of new enemies
+    pub radius: (f32, f32),
+    /// The smallest and largest base health of new enemies
+    pub health: (i32, i32),
+    /// The health a new enemy gains for every pixel of its radius, so larger enemies take more hits
+    #[serde(default)]
+    pub health_per_radius: f32,
+    /// The health a new enemy gains for every 1000 points of score
+    #[serde(default)]
+    pub health_per_1000_score: f32,
+    /// The smallest and largest armor of new enemies. Armor reduces the damage of every hit and cracks a bit with every hit
+    #[serde(default)]
+    pub armor: (i32, i32),
+    /// The speed of the enemies as a multiple of the [enemy speed](super::game_settings::GameSettings::enemy_speed_multiplier)
+    pub speed: f32,
+    /// The coins an enemy drops when it splits or is destroyed. This is multiplied by the coin multiplier
+    pub coins: i32,
+    /// How the enemies act
+    pub behaviour: ArchetypeBehaviour,
+    /// How likely a new enemy without a given archetype is of this archetype
+    pub spawn_weight: SpawnWeight,
+    /// How the enemies split. If this is none they never split
+    pub split: Option<SplitRule>,
+}
+
+impl Archetype {
+    /// Returns the archetypes of the bundled catalog. The catalog is only parsed once.
+    pub fn catalog() -> &'static [Archetype] {
+        static ARCHETYPES: OnceCell<Vec<Archetype>> = OnceCell::new();
+        ARCHETYPES.get_or_init(|| {
+            let archetypes: Vec<Archetype> =
+                serde_json::from_str(CATALOG).expect("The bundled archetype catalog is valid");
+            Archetype::validate_catalog(&archetypes)
+                .expect("The bundled archetype catalog is consistent");
+            archetypes
+        })
+    }
+
+    /// Checks that enemies can be created from the archetypes.
+    ///
+    /// The catalog must not be empty, the names must be unique, every split child must be in the catalog and every range must be ordered.
+    pub fn validate_catalog(archetypes: &[Archetype]) -> Result<(), CatalogError> {
+        if archetypes.is_empty() {
+            return Err(CatalogError::Empty);
+        }
+        for (index, archetype) in archetypes.iter().enumerate() {
+            let name = &archetype.name;
+            if archetypes[..index].iter().any(|other| &other.name == name) {
+                return Err(CatalogError::DuplicateName(name.clone()));
+            }
+            if !archetype.has_ordered_ranges() {
+                return Err(CatalogError::UnorderedRange(name.clone()));
+            }
+            if let Some(child) = archetype
+                .split
+                .as_ref()
+                .and_then(|split| split.child.as_ref())
+            {
+                if archetypes.iter().all(|other| &other.name != child) {
+                    return Err(CatalogError::UnknownChild(name.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the minimum of every range of the archetype is not above its maximum. Ranges with NaN are not ordered.
+    fn has_ordered_ranges(&self) -> bool {
+        let ordered = |(min, max): (f32, f32)| min <= max;
+        ordered(self.radius)
+            && self.health.0 <= self.health.1
+            && self.armor.0 <= self.armor.1
+            && match &self.split {
+                Some(split) => {
+                    ordered(split.reproduce_limit) && split.children.0 <= split.children.1
+                }
+                None => true,
+            }
+    }
+
+    /// Chooses the archetype of a new enemy by the [spawn weights](Archetype::spawn_weight) at the given score.
+    ///
+    /// Returns none if no archetype can spawn.
+    pub fn choose<'a>(
+        archetypes: &'a [Archetype],
+        score: f64,
+        rng: &mut impl Rng,
+    ) -> Option<&'a Archetype> {
+        let weights: Vec<f32> = archetypes
+            .iter()
+            .map(|archetype| archetype.spawn_weight.at(score).max(0.0))
+            .collect();
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0.0..total);
+        let mut last = None;
+        for (archetype, weight) in archetypes.iter().zip(weights) {
+            if weight <= 0.0 {
+                continue;
+            }
+            if roll < weight {
+                return Some(archetype);
+            }
+            roll -= weight;
+            last = Some(archetype);
+        }
+        // Rounding errors may leave a tiny part of the roll
+        last
+    }
+
+    /// Rolls the health of a new enemy with the given radius at the given score. It is at least 1.
+    pub fn roll_health(&self, radius: f32, score: f64, rng: &mut impl Rng) -> i32 {
+        let base = rng.gen_range(self.health.0..=self.health.1);
+        let bonus =
+            radius * self.health_per_radius + (score / 1000.0) as f32 * self.health_per_1000_score;
+        (base + bonus.floor() as i32).max(1)
+    }
+
+    /// Rolls the armor of a new enemy.
+    pub fn roll_armor(&self, rng: &mut impl Rng) -> i32 {
+        rng.gen_range(self.armor.0..=self.armor.1)
+    }
+
+    /// Returns true if the enemies of the archetype hunt the ship.
+    pub fn hunts(&self) -> bool {
+        self.behaviour == ArchetypeBehaviour::Hunt
+    }
+
+    /// Returns true if the enemies of the archetype fly in swarms.
+    pub fn swarms(&self) -> bool {
+        self.behaviour == ArchetypeBehaviour::Swarm
+    }
+}
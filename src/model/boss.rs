@@ -0,0 +1,307 @@
+//! Bosses are large enemies that appear when the score reaches one of the [boss scores](GameSettings::boss_scores).
+//!
+//! While a boss is on the field no other enemies spawn. The boss flies in from the top edge, moves from side to side and fires a different attack pattern in every phase.
+//! It can be hit anywhere, but hits on its weak points deal much more damage.
+
+use std::f32::consts::{PI, TAU};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{
+    collider::Collider,
+    game::TICK_DURATION,
+    game_settings::{field::Field, GameSettings},
+    id_generator::IdGenerator,
+    positioned::{Moves, Positioned},
+    projectile::Projectile,
+};
+
+/// The radius of a boss
+const BOSS_RADIUS: f32 = 80.0;
+
+/// The distance between the top edge of the field and a boss that finished flying in.
+const ENTRY_MARGIN: f32 = 40.0;
+
+/// The number of weak points on the rim of a boss
+const WEAK_POINTS: usize = 3;
+
+/// The radius of a weak point
+const WEAK_POINT_RADIUS: f32 = 12.0;
+
+/// How much more damage a hit on a weak point deals than a hit on the body of the boss
+pub const WEAK_POINT_MULTIPLIER: i32 = 5;
+
+/// The number of projectiles in the fan of the second phase
+const FAN_SHOTS: usize = 5;
+
+/// The angle in radians between two projectiles of the fan
+const FAN_SPREAD: f32 = 0.2;
+
+/// A large enemy with a health pool far beyond the one of [enemies](super::enemy::Enemy).
+///
+/// The boss fights in three phases. It enters the next phase when it lost another third of its health.
+#[derive(TS, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[ts(export)]
+pub struct Boss {
+    /// The id of the boss. This is used to identify the boss.
+    pub id: i64,
+    /// The x position of the boss
+    pub x: f32,
+    /// The y position of the boss
+    pub y: f32,
+    /// The x velocity of the boss in pixels per second
+    pub dx: f32,
+    /// The y velocity of the boss in pixels per second
+    pub dy: f32,
+    /// The radius of the boss
+    pub radius: f32,
+    /// The health of the boss
+    pub health: i32,
+    /// The health the boss spawned with
+    pub max_health: i32,
+    /// The rotation of the boss in radians. The boss spins faster in later phases
+    pub angle: f32,
+    /// The time in seconds until the boss attacks again
+    pub time_until_attack: f32,
+    /// The weak points on the rim of the boss. They turn with the boss
+    pub weak_points: Vec<WeakPoint>,
+}
+
+/// A spot on the rim of a [boss](Boss) that takes [more damage](WEAK_POINT_MULTIPLIER) than the rest of it.
+#[derive(TS, Clone, Copy, Serialize, Deserialize, PartialEq, Debug)]
+#[ts(export)]
+pub struct WeakPoint {
+    /// The x position of the weak point
+    pub x: f32,
+    /// The y position of the weak point
+    pub y: f32,
+    /// The radius of the weak point
+    pub radius: f32,
+    /// The angle of the weak point on the rim relative to the rotation of the boss
+    pub offset_angle: f32,
+}
+
+impl Boss {
+    /// Creates the boss with the given index, which is the number of bosses that appeared before it. Every boss has more health than the one before.
+    ///
+    /// The boss starts right above the top edge in the middle of the field.
+    pub fn new(game_settings: &GameSettings, index: usize, id: i64) -> Self {
+        let field = &game_settings.field;
+        let health = game_settings.boss_health * (index as i32 + 1);
+        let mut boss = Self {
+            id,
+            x: field.width as f32 / 2.0,
+            y: field.height as f32 + BOSS_RADIUS,
+            dx: 0.0,
+            dy: -game_settings.boss_speed,
+            radius: BOSS_RADIUS,
+            health,
+            max_health: health,
+            angle: 0.0,
+            time_until_attack: 0.0,
+            weak_points: (0..WEAK_POINTS)
+                .map(|i| WeakPoint {
+                    x: 0.0,
+                    y: 0.0,
+                    radius: WEAK_POINT_RADIUS,
+                    offset_angle: i as f32 / WEAK_POINTS as f32 * TAU,
+                })
+                .collect(),
+        };
+        boss.place_weak_points();
+        boss
+    }
+
+    /// Returns the phase of the boss, from 1 to 3. The boss enters the next phase when it lost another third of its health.
+    pub fn phase(&self) -> u8 {
+        let fraction = self.health_fraction();
+        if fraction > 2.0 / 3.0 {
+            1
+        } else if fraction > 1.0 / 3.0 {
+            2
+        } else {
+            3
+        }
+    }
+
+    /// Returns the health left as a fraction of the health the boss spawned with.
+    pub fn health_fraction(&self) -> f32 {
+        self.health.max(0) as f32 / self.max_health.max(1) as f32
+    }
+
+    /// Returns true while the boss is still flying onto the field. It doesn't attack until it arrived.
+    pub fn is_entering(&self, field: &Field) -> bool {
+        self.y > Self::resting_y(field, self.radius)
+    }
+
+    /// The y position the boss moves from side to side at.
+    fn resting_y(field: &Field, radius: f32) -> f32 {
+        field.height as f32 - radius - ENTRY_MARGIN
+    }
+
+    /// Moves the boss for one tick.
+    ///
+    /// The boss flies down until it is completely on the field and then moves from side to side, turning around at the edges.
+    pub fn fly(&mut self, game_settings: &GameSettings) {
+        let field = &game_settings.field;
+        if !self.is_entering(field) && self.dx == 0.0 {
+            self.dx = game_settings.boss_speed;
+            self.dy = 0.0;
+        }
+        if self.x - self.radius < 0.0 {
+            self.dx = self.dx.abs();
+        } else if self.x + self.radius > field.width as f32 {
+            self.dx = -self.dx.abs();
+        }
+
+        let (dx, dy) = self.travel();
+        self.x += dx;
+        self.y = (self.y + dy).max(Self::resting_y(field, self.radius));
+        self.angle = (self.angle + self.spin() * TICK_DURATION).rem_euclid(TAU);
+        self.place_weak_points();
+    }
+
+    /// Returns how fast the boss spins in radians per second.
+    fn spin(&self) -> f32 {
+        match self.phase() {
+            1 => 0.3,
+            2 => 0.6,
+            _ => 2.0,
+        }
+    }
+
+    /// Moves the weak points to their places on the rim of the boss.
+    fn place_weak_points(&mut self) {
+        let (x, y, radius, angle) = (self.x, self.y, self.radius, self.angle);
+        for point in &mut self.weak_points {
+            let (sin, cos) = (angle + point.offset_angle).sin_cos();
+            point.x = x + cos * radius * 0.8;
+            point.y = y + sin * radius * 0.8;
+        }
+    }
+
+    /// Counts down the attack timer and returns the projectiles of the attack once it ran out.
+    ///
+    /// The first phase aims single shots at the ship, the second phase fires a fan of shots at it and the third phase sprays a fast spiral of shots in every direction.
+    pub fn attack(
+        &mut self,
+        game_settings: &GameSettings,
+        generator: &IdGenerator,
+    ) -> Vec<Projectile> {
+        if self.is_entering(&game_settings.field) {
+            return vec![];
+        }
+        if self.time_until_attack > 0.0 {
+            self.time_until_attack -= TICK_DURATION;
+            return vec![];
+        }
+
+        let position = self.get_center();
+        let (ship_x, ship_y) = game_settings
+            .field
+            .nearest_image(position, game_settings.ship.get_center());
+        let aim = (ship_y - position.1).atan2(ship_x - position.0);
+        let (timeout, angles) = match self.phase() {
+            1 => (1.5, vec![aim]),
+            2 => (
+                2.0,
+                (0..FAN_SHOTS)
+                    .map(|i| aim + (i as f32 - (FAN_SHOTS - 1) as f32 / 2.0) * FAN_SPREAD)
+                    .collect(),
+            ),
+            _ => (0.2, vec![self.angle, self.angle + PI]),
+        };
+        self.time_until_attack = timeout;
+
+        angles
+            .into_iter()
+            .map(|angle| {
+                Projectile::hostile_towards(
+                    position,
+                    (angle.cos(), angle.sin()),
+                    self.radius,
+                    game_settings,
+                    generator.next_id(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the damage the projectile deals to the boss if it hit the boss in its last move. Hits on a weak point deal [more damage](WEAK_POINT_MULTIPLIER).
+    pub fn damage_by(&self, projectile: &Projectile, field: &Field) -> Option<i32> {
+        if self
+            .weak_points
+            .iter()
+            .any(|point| projectile.is_swept_collision(point, field))
+        {
+            Some(projectile.damage * WEAK_POINT_MULTIPLIER)
+        } else if projectile.is_swept_collision(self, field) {
+            Some(projectile.damage)
+        } else {
+            None
+        }
+    }
+}
+
+/// The outline of a boss with a radius of 1. The boss is a regular octagon.
+fn outline() -> Vec<(f32, f32)> {
+    (0..8)
+        .map(|i| {
+            let angle = i as f32 / 8.0 * TAU;
+            (angle.cos(), angle.sin())
+        })
+        .collect()
+}
+
+impl Positioned for Boss {
+    fn collider(&self) -> Collider {
+        Collider::Polygon(outline())
+            .scaled(self.radius)
+            .rotated(self.angle)
+    }
+
+    fn get_position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+        self.place_weak_points();
+    }
+
+    fn dimensions(&self) -> (f32, f32) {
+        (self.radius * 2.0, self.radius * 2.0)
+    }
+}
+
+impl Moves for Boss {
+    fn direction(&self) -> (f32, f32) {
+        (self.dx, self.dy)
+    }
+
+    fn set_direction(&mut self, dx: f32, dy: f32) {
+        self.dx = dx;
+        self.dy = dy;
+    }
+}
+
+impl Positioned for WeakPoint {
+    fn collider(&self) -> Collider {
+        Collider::Circle(self.radius)
+    }
+
+    fn get_position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn dimensions(&self) -> (f32, f32) {
+        (self.radius * 2.0, self.radius * 2.0)
+    }
+}
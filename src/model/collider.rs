@@ -0,0 +1,294 @@
+//! This module contains the shapes used for the collision detection.
+//!
+//! Collisions are detected with the separating axis theorem: two convex shapes don't collide if and only if there is an axis
+//! on which their projections don't overlap. For polygons only the normals of their edges have to be checked.
+
+use serde::{Deserialize, Serialize};
+
+/// The shape of an object. The coordinates are relative to the center of the object.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Collider {
+    /// A circle with the given radius
+    Circle(f32),
+    /// A convex polygon with the given vertices in order
+    Polygon(Vec<(f32, f32)>),
+}
+
+/// The result of a collision of two colliders.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Penetration {
+    /// The direction the other collider has to be moved in to separate the colliders. It has a length of 1.
+    pub normal: (f32, f32),
+    /// How far the other collider has to be moved to separate the colliders
+    pub depth: f32,
+}
+
+impl Collider {
+    /// Creates an axis aligned rectangle with the given width and height.
+    pub fn rectangle(width: f32, height: f32) -> Self {
+        let (x, y) = (width / 2.0, height / 2.0);
+        Collider::Polygon(vec![(-x, -y), (x, -y), (x, y), (-x, y)])
+    }
+
+    /// Returns the collider scaled by the factor.
+    pub fn scaled(&self, factor: f32) -> Self {
+        match self {
+            Collider::Circle(radius) => Collider::Circle(radius * factor),
+            Collider::Polygon(vertices) => Collider::Polygon(
+                vertices
+                    .iter()
+                    .map(|(x, y)| (x * factor, y * factor))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the collider rotated counter-clockwise by the angle in radians.
+    pub fn rotated(&self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        match self {
+            Collider::Circle(radius) => Collider::Circle(*radius),
+            Collider::Polygon(vertices) => Collider::Polygon(
+                vertices
+                    .iter()
+                    .map(|(x, y)| (x * cos - y * sin, x * sin + y * cos))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Checks if the collider at the position collides with the other collider at its position.
+    pub fn intersects(
+        &self,
+        position: (f32, f32),
+        other: &Collider,
+        other_position: (f32, f32),
+    ) -> bool {
+        self.penetration(position, other, other_position).is_some()
+    }
+
+    /// Returns how far the colliders overlap if they collide.
+    ///
+    /// The normal points from this collider towards the other collider along the axis they overlap the least on.
+    /// Colliders that only touch don't collide.
+    pub fn penetration(
+        &self,
+        position: (f32, f32),
+        other: &Collider,
+        other_position: (f32, f32),
+    ) -> Option<Penetration> {
+        let offset = (other_position.0 - position.0, other_position.1 - position.1);
+
+        if let (Collider::Circle(radius), Collider::Circle(other_radius)) = (self, other) {
+            let distance = length(offset);
+            let depth = radius + other_radius - distance;
+            if depth <= 0.0 {
+                return None;
+            }
+            // Circles at the exact same position are separated horizontally
+            let normal = if distance > 0.0 {
+                (offset.0 / distance, offset.1 / distance)
+            } else {
+                (1.0, 0.0)
+            };
+            return Some(Penetration { normal, depth });
+        }
+
+        let mut axes = self.axes(position, other, other_position);
+        axes.extend(other.axes(other_position, self, position));
+
+        let mut penetration: Option<Penetration> = None;
+        for axis in axes {
+            let (min, max) = self.project(position, axis);
+            let (other_min, other_max) = other.project(other_position, axis);
+            let depth = max.min(other_max) - min.max(other_min);
+            if depth <= 0.0 {
+                return None;
+            }
+            if !matches!(penetration, Some(p) if p.depth <= depth) {
+                let normal = if dot(offset, axis) < 0.0 {
+                    (-axis.0, -axis.1)
+                } else {
+                    axis
+                };
+                penetration = Some(Penetration { normal, depth });
+            }
+        }
+        penetration
+    }
+
+    /// Checks if the collider collides with the other collider at any point while it moves from the start to the end position.
+    ///
+    /// The collider sweeps over a convex shape during the move, so this is tested analytically: two circles collide if the closest point of the move
+    /// is near enough to the other circle. Otherwise the swept shape is checked with the separating axis theorem, where the projection of the swept
+    /// shape spans the projections at the start and the end of the move.
+    pub fn swept_intersects(
+        &self,
+        start: (f32, f32),
+        end: (f32, f32),
+        other: &Collider,
+        other_position: (f32, f32),
+    ) -> bool {
+        if let (Collider::Circle(radius), Collider::Circle(other_radius)) = (self, other) {
+            let closest = closest_point(start, end, other_position);
+            let offset = (other_position.0 - closest.0, other_position.1 - closest.1);
+            return length(offset) < radius + other_radius;
+        }
+
+        let travel = (end.0 - start.0, end.1 - start.1);
+        let mut axes = self.edge_normals();
+        axes.extend(other.edge_normals());
+        // The sides of the swept shape run along the move
+        axes.extend(normalize((-travel.1, travel.0)));
+        match (self, other) {
+            // The round ends of a swept circle are separated from a vertex along the axis to the closest point of the move
+            (Collider::Circle(_), Collider::Polygon(vertices)) => {
+                for vertex in vertices {
+                    let vertex = (other_position.0 + vertex.0, other_position.1 + vertex.1);
+                    let closest = closest_point(start, end, vertex);
+                    axes.extend(normalize((vertex.0 - closest.0, vertex.1 - closest.1)));
+                }
+            }
+            // A circle is separated from a vertex of the swept polygon along the axis between them
+            (Collider::Polygon(vertices), Collider::Circle(_)) => {
+                for position in [start, end] {
+                    for vertex in vertices {
+                        axes.extend(normalize((
+                            other_position.0 - position.0 - vertex.0,
+                            other_position.1 - position.1 - vertex.1,
+                        )));
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        axes.into_iter().all(|axis| {
+            let (start_min, start_max) = self.project(start, axis);
+            let (end_min, end_max) = self.project(end, axis);
+            let (other_min, other_max) = other.project(other_position, axis);
+            start_max.max(end_max).min(other_max) - start_min.min(end_min).max(other_min) > 0.0
+        })
+    }
+
+    /// Returns the normals of the edges of a polygon. A circle has no edges.
+    fn edge_normals(&self) -> Vec<(f32, f32)> {
+        match self {
+            Collider::Circle(_) => vec![],
+            Collider::Polygon(vertices) => vertices
+                .iter()
+                .zip(vertices.iter().cycle().skip(1))
+                .filter_map(|(a, b)| normalize((a.1 - b.1, b.0 - a.0)))
+                .collect(),
+        }
+    }
+
+    /// Returns the axes the collider has to be checked on against the other collider.
+    ///
+    /// These are the normals of the edges of a polygon.
+    /// A circle has no edges, so against a circle the polygon adds the axis from its nearest vertex to the circle instead.
+    fn axes(
+        &self,
+        position: (f32, f32),
+        other: &Collider,
+        other_position: (f32, f32),
+    ) -> Vec<(f32, f32)> {
+        let vertices = match self {
+            Collider::Circle(_) => return vec![],
+            Collider::Polygon(vertices) => vertices,
+        };
+        let mut axes = self.edge_normals();
+        if let Collider::Polygon(_) = other {
+            return axes;
+        }
+
+        // The axis between a circle and the polygon goes through the vertex closest to the circle
+        let relative = (other_position.0 - position.0, other_position.1 - position.1);
+        if let Some(closest) = vertices.iter().min_by(|a, b| {
+            let distance = |v: &&(f32, f32)| length((relative.0 - v.0, relative.1 - v.1));
+            distance(a)
+                .partial_cmp(&distance(b))
+                .expect("The vertices are never NaN")
+        }) {
+            axes.extend(normalize((relative.0 - closest.0, relative.1 - closest.1)));
+        }
+        axes
+    }
+
+    /// Projects the collider at the position onto the axis. Returns the minimum and maximum of the projection.
+    fn project(&self, position: (f32, f32), axis: (f32, f32)) -> (f32, f32) {
+        let center = dot(position, axis);
+        match self {
+            Collider::Circle(radius) => (center - radius, center + radius),
+            Collider::Polygon(vertices) => vertices
+                .iter()
+                .map(|&vertex| center + dot(vertex, axis))
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), p| {
+                    (min.min(p), max.max(p))
+                }),
+        }
+    }
+}
+
+/// Returns the vertices of the smallest convex polygon that contains all points, in counter-clockwise order.
+pub fn convex_hull(mut points: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    points.sort_by(|a, b| a.partial_cmp(b).expect("The points are never NaN"));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    // Andrew's monotone chain: build the lower and the upper half of the hull counter-clockwise
+    let cross = |o: (f32, f32), a: (f32, f32), b: (f32, f32)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let mut hull: Vec<(f32, f32)> = vec![];
+    for pass in 0..2 {
+        let start = hull.len();
+        for &point in &points {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point of each half is the first point of the other half
+        hull.pop();
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+    hull
+}
+
+/// Returns the point on the segment from the start to the end that is closest to the point.
+fn closest_point(start: (f32, f32), end: (f32, f32), point: (f32, f32)) -> (f32, f32) {
+    let segment = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dot(segment, segment);
+    if length_squared == 0.0 {
+        return start;
+    }
+    let t = (dot((point.0 - start.0, point.1 - start.1), segment) / length_squared).clamp(0.0, 1.0);
+    (start.0 + segment.0 * t, start.1 + segment.1 * t)
+}
+
+/// Returns the dot product of two vectors.
+fn dot(a: (f32, f32), b: (f32, f32)) -> f32 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+/// Returns the length of a vector.
+fn length(vector: (f32, f32)) -> f32 {
+    dot(vector, vector).sqrt()
+}
+
+/// Returns the vector with a length of 1, or none if the vector has no length.
+fn normalize(vector: (f32, f32)) -> Option<(f32, f32)> {
+    let length = length(vector);
+    if length > 0.0 {
+        Some((vector.0 / length, vector.1 / length))
+    } else {
+        None
+    }
+}
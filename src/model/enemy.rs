@@ -1,49 +1,32 @@
-//! This module contains the enemy model. It contains the enemy type and the enemy struct.
+//! This module contains the enemy model. What kind of enemy it is is described by its [archetype](super::archetype::Archetype).
 
-use std::hash::{Hash, Hasher};
+use std::{
+    f32::consts::TAU,
+    hash::{Hash, Hasher},
+};
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use unique_id::{sequence::SequenceGenerator, Generator};
-use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
 use super::{
-    game_settings::GameSettings,
+    archetype::Archetype,
+    collider::{convex_hull, Collider},
+    game_settings::{field::Field, GameSettings},
+    id_generator::IdGenerator,
     positioned::{Moves, Positioned},
+    steering::Behaviour,
+    wave::{Edge, WaveGroup},
 };
 
-/// The type of the enemy. This determines the enemy's appearance and behavior.
-#[wasm_bindgen]
-#[derive(Clone, Copy, Serialize, Deserialize, PartialEq)]
-pub enum EnemyType {
-    Asteroid,
-    Ship,
-    Debris,
-}
-
-impl EnemyType {
-    /// Creates a new enemy type.
-    ///
-    /// The chance that the enemy will be a ship will increase over time and with the score
-    fn new(game_settings: &GameSettings, score: f64) -> Self {
-        let mut rng = thread_rng();
-        // The chance that the enemy will be a ship will increase over time and with the score
-        let asteroid_probability =
-            1.0 - (score / 1000.0).min(game_settings.asteroid_probability as f64);
-        if rng.gen_bool(asteroid_probability) {
-            EnemyType::Asteroid
-        } else {
-            EnemyType::Ship
-        }
-    }
-}
-
 /// The model of an enemy. It contains all the information about the enemy state.
 #[wasm_bindgen]
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Enemy {
-    dy: f32,
-    dx: f32,
+    /// The y velocity of the enemy in pixels per second
+    pub dy: f32,
+    /// The x velocity of the enemy in pixels per second
+    pub dx: f32,
     /// The id of the enemy. This is used to identify the enemy.
     pub id: i64,
     /// The x position of the enemy
@@ -54,80 +37,173 @@ pub struct Enemy {
     pub radius: f32,
     /// The radius limit below that the enemy will not split into smaller enemies on hit
     reproduce_limit: f32,
-    /// The type of the enemy
-    pub enemy_type: EnemyType,
-    /// The health of the enemy
+    /// The name of the [archetype](Archetype) of the enemy. This determines the enemy's appearance and behavior.
+    #[wasm_bindgen(skip)]
+    pub enemy_type: String,
+    /// The health of the enemy. The enemy is destroyed or splits once it is depleted
     pub health: i32,
+    /// The health the enemy spawned with. Enemy ships that lost health flee from the ship
+    #[serde(default = "default_max_health")]
+    pub max_health: i32,
+    /// The armor of the enemy. It is subtracted from the damage of every hit and cracks by one point with every hit
+    #[serde(default)]
+    pub armor: i32,
+    /// What the enemy is currently doing. Only enemy ships [steer](crate::steering::steer)
+    #[serde(default)]
+    pub behaviour: Behaviour,
+    /// The time in seconds until an enemy ship can shoot again
+    #[serde(default)]
+    pub time_until_next_shot: f32,
+    /// The vertices of the jagged outline of the enemy relative to its center, in multiples of its radius.
+    ///
+    /// Enemies saved without an outline are round.
+    #[wasm_bindgen(skip)]
+    #[serde(default)]
+    pub outline: Vec<(f32, f32)>,
+}
+
+/// Enemies saved before they had a maximum health are treated as undamaged.
+fn default_max_health() -> i32 {
+    1
 }
 
+#[wasm_bindgen]
 impl Enemy {
-    /// Creates a new enemy with a random position and speed
-    pub fn new(game_settings: &GameSettings, score: f64, id: i64) -> Self {
-        let mut rng = thread_rng();
-        let radius: f32 = rng.gen_range(20.0..40.0);
-        let reproduce_limit = rng.gen_range(5.0..10.0);
-
-        let enemy_type = EnemyType::new(&game_settings, score);
-
-        let health = rng.gen_range(1..2);
-
-        let (x, y) = if rng.gen_bool(0.5) {
-            (
-                rng.gen_range(0.0..(game_settings.field.width + radius.ceil() as i32) as f32),
-                if rng.gen_bool(0.5) {
-                    0.0 - radius.ceil()
-                } else {
-                    game_settings.field.height as f32 + radius.ceil()
-                },
-            )
-        } else {
-            (
-                if rng.gen_bool(0.5) {
-                    0.0 - radius.ceil()
-                } else {
-                    game_settings.field.width as f32 + radius.ceil()
-                },
-                rng.gen_range(0.0..(game_settings.field.height + radius.ceil() as i32) as f32),
-            )
+    /// Returns the outline of the enemy as an array of `[x, y]` pairs. See [outline](Enemy::outline).
+    #[wasm_bindgen(getter)]
+    pub fn outline(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.outline).unwrap()
+    }
+
+    /// Returns the name of the archetype of the enemy. See [enemy_type](Enemy::enemy_type).
+    #[wasm_bindgen(getter)]
+    pub fn enemy_type(&self) -> String {
+        self.enemy_type.clone()
+    }
+}
+
+impl Enemy {
+    /// Creates a new enemy with a random type, position and speed
+    pub fn new(game_settings: &GameSettings, score: f64, id: i64, rng: &mut impl Rng) -> Self {
+        Self::from_group(game_settings, &WaveGroup::new(None, 1), score, id, rng)
+    }
+
+    /// Creates a new enemy of a [wave group](WaveGroup) at a random position on the edge of the group.
+    ///
+    /// If the group has no enemy type, or one that isn't in the [archetypes](GameSettings::archetypes), the archetype is chosen by the score.
+    pub fn from_group(
+        game_settings: &GameSettings,
+        group: &WaveGroup,
+        score: f64,
+        id: i64,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let archetype = match group
+            .enemy_type
+            .as_deref()
+            .and_then(|name| game_settings.archetype(name))
+        {
+            Some(archetype) => archetype,
+            None => Archetype::choose(&game_settings.archetypes, score, rng)
+                .or_else(|| game_settings.archetypes.first())
+                .expect("There is at least one archetype"),
         };
-        let (dx, dy) = Enemy::calculate_speed(&game_settings, (x, y), &enemy_type);
+
+        let (min_radius, max_radius) = group.radius.unwrap_or(archetype.radius);
+        let radius: f32 = rng.gen_range(min_radius..=max_radius);
+        let reproduce_limit = match &archetype.split {
+            Some(split) => rng.gen_range(split.reproduce_limit.0..=split.reproduce_limit.1),
+            None => 0.0,
+        };
+        let health = archetype.roll_health(radius, score, rng);
+        let armor = archetype.roll_armor(rng);
+
+        let outline = Enemy::generate_outline(rng);
+
         // Spawn the enemy at the edge of the field
+        let (x, y) = Enemy::spawn_position(&game_settings.field, radius, group.edge, rng);
+        let (dx, dy) = Enemy::calculate_speed(game_settings, (x, y), archetype.hunts(), rng);
+        let speed = archetype.speed * group.speed;
 
         Self {
             id,
-            dy,
-            dx,
+            dy: dy * speed,
+            dx: dx * speed,
             x,
             y,
             radius,
             reproduce_limit,
-            enemy_type,
+            enemy_type: archetype.name.clone(),
             health,
+            max_health: health,
+            armor,
+            behaviour: Behaviour::Drift,
+            time_until_next_shot: game_settings.enemy_shoot_timeout(score),
+            outline,
+        }
+    }
+
+    /// Returns a random position right outside of the given edge of the field.
+    fn spawn_position(field: &Field, radius: f32, edge: Edge, rng: &mut impl Rng) -> (f32, f32) {
+        let edge = match edge {
+            Edge::Any => [Edge::Top, Edge::Bottom, Edge::Left, Edge::Right][rng.gen_range(0..4)],
+            edge => edge,
+        };
+        let margin = radius.ceil();
+        match edge {
+            Edge::Bottom => (rng.gen_range(0.0..field.width as f32 + margin), -margin),
+            Edge::Top => (
+                rng.gen_range(0.0..field.width as f32 + margin),
+                field.height as f32 + margin,
+            ),
+            Edge::Left => (-margin, rng.gen_range(0.0..field.height as f32 + margin)),
+            _ => (
+                field.width as f32 + margin,
+                rng.gen_range(0.0..field.height as f32 + margin),
+            ),
         }
     }
 
+    /// Generates a random convex outline with a radius of 1.
+    ///
+    /// The vertices are spread around the center with a random distance, so every enemy looks a bit different.
+    fn generate_outline(rng: &mut impl Rng) -> Vec<(f32, f32)> {
+        let vertices = rng.gen_range(7..12);
+        let points = (0..vertices)
+            .map(|i| {
+                let angle = (i as f32 + rng.gen_range(-0.3..0.3)) / vertices as f32 * TAU;
+                let distance = rng.gen_range(0.7..1.0);
+                (angle.cos() * distance, angle.sin() * distance)
+            })
+            .collect();
+        convex_hull(points)
+    }
+
     /// Calculates the speed of the enemy based on the game settings and the enemy type.
     ///
-    /// If the enemy is a ship it has a chance that it will fly directly towards the ship.
+    /// If the enemy hunts the ship it has a chance that it will fly directly towards the ship.
+    /// On a toroidal field it takes the shortest way to the ship, which may lead across the edges of the field.
     fn calculate_speed(
         game_settings: &GameSettings,
         pos: (f32, f32),
-        enemy_type: &EnemyType,
+        hunts: bool,
+        rng: &mut impl Rng,
     ) -> (f32, f32) {
-        let mut rng = thread_rng();
         let speed_multi = game_settings.enemy_speed_multiplier;
         loop {
-            let (mut dx, mut dy) = (
+            let (dx, dy) = (
                 rng.gen_range(-speed_multi..speed_multi),
                 rng.gen_range(-speed_multi..speed_multi),
             );
 
             let probability = rng.gen_range(0.0..10.0);
 
-            // If the enemy is a ship and the probability is less than the ship visibility:
+            // If the enemy hunts and the probability is less than the ship visibility:
             // The enemy will move directly towards the ship
-            if probability < game_settings.ship_visibility && *enemy_type == EnemyType::Ship {
-                let (ship_x, ship_y) = game_settings.ship.get_position();
+            if probability < game_settings.ship_visibility && hunts {
+                let (ship_x, ship_y) = game_settings
+                    .field
+                    .nearest_image(pos, game_settings.ship.get_position());
 
                 let (diff_x, diff_y) = (ship_x - pos.0, ship_y - pos.1);
 
@@ -135,8 +211,11 @@ impl Enemy {
 
                 let (norm_dx, norm_dy) = (diff_x / length, diff_y / length);
 
-                dx = norm_dx * game_settings.enemy_speed_multiplier;
-                dy = norm_dy * game_settings.enemy_speed_multiplier;
+                // The ship may lie in any direction, so the minimum speed applies to the whole speed and not to dx and dy
+                return (
+                    norm_dx * game_settings.enemy_speed_multiplier,
+                    norm_dy * game_settings.enemy_speed_multiplier,
+                );
             }
 
             if dx.abs() >= game_settings.enemy_min_speed
@@ -149,58 +228,146 @@ impl Enemy {
 
     /// Applies damage to the enemy.
     ///
-    /// The enemy will split into smaller enemies if it is bigger than the reproduce limit.
-    /// If the enemy is dead the method will return an empty vec.
+    /// The [armor](Enemy::armor) of the enemy absorbs a part of the damage and cracks with every hit.
+    /// The enemy survives the hit as long as it has health left.
+    /// Otherwise it will split into smaller enemies if it is bigger than the reproduce limit and its archetype has a [split rule](crate::archetype::SplitRule).
+    /// The health of the children depends on their own size and the score.
+    /// If the enemy is destroyed the method will return an empty vec.
     pub fn take_damage(
         &self,
         game_settings: &GameSettings,
         damage: i32,
-        generator: SequenceGenerator,
+        score: f64,
+        generator: &IdGenerator,
+        rng: &mut impl Rng,
     ) -> Vec<Self> {
         if self.health <= 0 {
             return vec![];
         }
-        if self.radius <= self.reproduce_limit {
-            let mut new_enemy = self.clone();
-            new_enemy.health -= damage;
-            return vec![new_enemy];
+        let mut damaged = self.clone();
+        damaged.health -= self.damage_after_armor(damage);
+        damaged.armor = (self.armor - 1).max(0);
+        if damaged.health > 0 {
+            return vec![damaged];
         }
-        let mut rng = thread_rng();
+
+        let split = match self.archetype(game_settings).and_then(|a| a.split.as_ref()) {
+            Some(split) if self.radius > self.reproduce_limit => split,
+            _ => return vec![],
+        };
+        let child_type = split.child.as_ref().unwrap_or(&self.enemy_type);
+        let child_archetype = game_settings.archetype(child_type);
+        let (hunts, speed) = child_archetype.map_or((false, 1.0), |a| (a.hunts(), a.speed));
+
         let mut children = vec![];
-        for _ in 0..rng.gen_range(2..4) {
+        for _ in 0..rng.gen_range(split.children.0..=split.children.1) {
             let mut child = self.clone();
             child.id = generator.next_id();
             child.radius /= 2.0;
-            child.health /= 2;
+            child.health = child_archetype.map_or(1, |a| a.roll_health(child.radius, score, rng));
+            child.max_health = child.health;
+            child.armor = child_archetype.map_or(0, |a| a.roll_armor(rng));
             child.reproduce_limit /= 2.0;
+            child.outline = Enemy::generate_outline(rng);
             // Make sure the child spawns in the radius of the parent
             child.x += rng.gen_range(-self.radius / 2.0..self.radius / 2.0);
             child.y += rng.gen_range(-self.radius / 2.0..self.radius / 2.0);
-            if self.enemy_type == EnemyType::Ship {
-                child.enemy_type = EnemyType::Debris;
-            }
+            child.enemy_type = child_type.clone();
 
-            let (dx, dy) =
-                Enemy::calculate_speed(&game_settings, (child.x, child.y), &child.enemy_type);
-            child.dx = dx;
-            child.dy = dy;
+            let (dx, dy) = Enemy::calculate_speed(game_settings, (child.x, child.y), hunts, rng);
+            child.dx = dx * speed;
+            child.dy = dy * speed;
 
             children.push(child);
         }
         children
     }
 
+    /// Returns the damage a hit with the given damage deals after the armor of the enemy absorbed its part.
+    pub fn damage_after_armor(&self, damage: i32) -> i32 {
+        (damage - self.armor).max(0)
+    }
+
+    /// Returns the mass of the enemy. It grows with the area of the enemy.
+    pub fn mass(&self) -> f32 {
+        self.radius * self.radius
+    }
+
+    /// Bounces the enemy off another enemy if they collide on the field.
+    ///
+    /// The enemies are pushed apart until they no longer collide, the lighter enemy is pushed further.
+    /// If they move towards each other they exchange momentum along the direction they are pushed in.
+    /// A restitution of 1 is a perfectly elastic bounce, lower values dampen it.
+    pub fn bounce(&mut self, other: &mut Enemy, restitution: f32, field: &Field) {
+        let position = self.get_center();
+        let penetration = match self.collider().penetration(
+            position,
+            &other.collider(),
+            field.nearest_image(position, other.get_center()),
+        ) {
+            Some(penetration) => penetration,
+            None => return,
+        };
+        let (norm_x, norm_y) = penetration.normal;
+        let (inverse_mass, other_inverse_mass) = (1.0 / self.mass(), 1.0 / other.mass());
+        let total_inverse_mass = inverse_mass + other_inverse_mass;
+
+        // Push the enemies apart
+        let correction = penetration.depth / total_inverse_mass;
+        self.x -= norm_x * correction * inverse_mass;
+        self.y -= norm_y * correction * inverse_mass;
+        other.x += norm_x * correction * other_inverse_mass;
+        other.y += norm_y * correction * other_inverse_mass;
+
+        // Enemies that already move apart keep their speed
+        let approach_speed = (other.dx - self.dx) * norm_x + (other.dy - self.dy) * norm_y;
+        if approach_speed >= 0.0 {
+            return;
+        }
+        let impulse = -(1.0 + restitution) * approach_speed / total_inverse_mass;
+        self.dx -= norm_x * impulse * inverse_mass;
+        self.dy -= norm_y * impulse * inverse_mass;
+        other.dx += norm_x * impulse * other_inverse_mass;
+        other.dy += norm_y * impulse * other_inverse_mass;
+    }
+
+    /// Returns the archetype of the enemy, or none if it isn't in the [archetypes](GameSettings::archetypes).
+    pub fn archetype<'a>(&self, game_settings: &'a GameSettings) -> Option<&'a Archetype> {
+        game_settings.archetype(&self.enemy_type)
+    }
+
+    /// Returns true if the enemy flies in a [swarm](Archetype::swarms).
+    pub fn swarms(&self, game_settings: &GameSettings) -> bool {
+        matches!(self.archetype(game_settings), Some(archetype) if archetype.swarms())
+    }
+
+    /// Moves the enemy next to the leader of its swarm, so the swarm enters the field together.
+    ///
+    /// The enemy stays on the edge of the field the leader enters from and flies in the same direction as the leader.
+    pub fn join(&mut self, leader: &Enemy, game_settings: &GameSettings, rng: &mut impl Rng) {
+        let spread = game_settings.swarm_perception / 2.0;
+        let offset = rng.gen_range(-spread..spread);
+        let field = &game_settings.field;
+        if leader.x < 0.0 || leader.x > field.width as f32 {
+            self.set_position(leader.x, leader.y + offset);
+        } else {
+            self.set_position(leader.x + offset, leader.y);
+        }
+        self.set_direction(leader.dx, leader.dy);
+    }
+
+    /// Returns true if the enemy [hunts](Archetype::hunts) the ship.
+    pub fn hunts(&self, game_settings: &GameSettings) -> bool {
+        matches!(self.archetype(game_settings), Some(archetype) if archetype.hunts())
+    }
+
     /// Returns the amount of coins the enemy drops when it dies.
     ///
-    /// The amount of coins depends on the enemy type.
+    /// The amount of coins depends on the archetype of the enemy.
     /// The number gets multiplied by the coin multiplier in the game settings.
-    pub fn get_coins(&self) -> i32 {
-        match self.enemy_type {
-            EnemyType::Asteroid => 1,
-            EnemyType::Ship => 2,
-            // Get rewards for cleaning up the space
-            EnemyType::Debris => 4,
-        }
+    pub fn get_coins(&self, game_settings: &GameSettings) -> i32 {
+        self.archetype(game_settings)
+            .map_or(0, |archetype| archetype.coins)
     }
 }
 
@@ -221,8 +388,12 @@ impl Hash for Enemy {
 impl Eq for Enemy {}
 
 impl Positioned for Enemy {
-    fn is_round(&self) -> bool {
-        true
+    fn collider(&self) -> Collider {
+        if self.outline.is_empty() {
+            Collider::Circle(self.radius)
+        } else {
+            Collider::Polygon(self.outline.clone()).scaled(self.radius)
+        }
     }
     fn get_position(&self) -> (f32, f32) {
         (self.x, self.y)
@@ -239,6 +410,11 @@ impl Positioned for Enemy {
 
 impl Moves for Enemy {
     fn direction(&self) -> (f32, f32) {
-        (self.dx as f32, self.dy as f32)
+        (self.dx, self.dy)
+    }
+
+    fn set_direction(&mut self, dx: f32, dy: f32) {
+        self.dx = dx;
+        self.dy = dy;
     }
 }
@@ -1,10 +1,12 @@
 //! This module contains the ErrorMessage struct. This struct is used to display error messages to the user. It contains methods to dismiss this error message after a certain amount of time and a macro to simplify the creation of error messages.
 
+use super::game::TICK_DURATION;
+
 /// This error macro is used facilitate to creation of a new error message. Macros allow for default values of arguments and are easier to use.
 #[macro_export]
 macro_rules! error {
     ($a: expr) => {
-        ErrorMessage::new_dismissable($a, 100)
+        ErrorMessage::new_dismissable($a, 2.0)
     };
     ($a: expr, $b: expr) => {
         ErrorMessage::new_dismissable($a, $b)
@@ -18,10 +20,10 @@ pub struct ErrorMessage {
     message: Option<&'static str>,
     /// Whether the error message is dismissable. If it is dismissable, the user can click on the error message to dismiss it.
     dismissable: bool,
-    /// The time since the error message was created. This is used to dismiss the error message after a certain amount of time.
-    time_since_creation: i32,
-    /// The duration of the error message. This is used to dismiss the error message after a certain amount of time.
-    message_duration: i32,
+    /// The time in seconds since the error message was created. This is used to dismiss the error message after a certain amount of time.
+    time_since_creation: f32,
+    /// The duration of the error message in seconds. This is used to dismiss the error message after a certain amount of time.
+    message_duration: f32,
 }
 
 impl ErrorMessage {
@@ -30,27 +32,27 @@ impl ErrorMessage {
         Self {
             message: None,
             dismissable: false,
-            time_since_creation: 0,
-            message_duration: 0,
+            time_since_creation: 0.0,
+            message_duration: 0.0,
         }
     }
 
     /// Creates a new error message. This is used to create an error message with a message and a duration.
-    pub fn new_dismissable(message: &'static str, duration: i32) -> Self {
+    pub fn new_dismissable(message: &'static str, duration: f32) -> Self {
         Self {
             message: Some(message),
-            dismissable: false,
-            time_since_creation: 0,
+            dismissable: true,
+            time_since_creation: 0.0,
             message_duration: duration,
         }
     }
 
-    /// Creates a new error message. This is used to create an error message with a message and a duration.
-    pub fn new_non_dismissable(message: &'static str, duration: i32) -> Self {
+    /// Creates a new error message that the user can't dismiss. It is only removed once its duration ran out.
+    pub fn new_non_dismissable(message: &'static str, duration: f32) -> Self {
         Self {
             message: Some(message),
-            dismissable: true,
-            time_since_creation: 0,
+            dismissable: false,
+            time_since_creation: 0.0,
             message_duration: duration,
         }
     }
@@ -60,7 +62,7 @@ impl ErrorMessage {
         if self.is_expired() {
             self.message = None;
         } else {
-            self.time_since_creation += 1;
+            self.time_since_creation += TICK_DURATION;
         }
     }
 
@@ -78,10 +80,15 @@ impl ErrorMessage {
         }
     }
 
+    /// Returns whether the user can dismiss the error message.
+    pub fn is_dismissable(&self) -> bool {
+        self.dismissable
+    }
+
     /// Sets the message of the error message.
     pub fn set_error_message(&mut self, message: &'static str) {
         self.message = Some(message);
-        self.time_since_creation = 0;
+        self.time_since_creation = 0.0;
     }
 
     /// Dismisses the error message.
@@ -1,27 +1,64 @@
 //! The heart of the game is the game struct. It contains all the information about the game state.
 //!
 //! The central game loop is implemented here using a state machine.
+//!
+//! The game keeps a bounded history of snapshots, which allows to rewind it and step forward again.
+
+use std::collections::VecDeque;
 
-use unique_id::{sequence::SequenceGenerator, Generator};
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
-use crate::game_ressources::GameRessource;
+use crate::game_ressources::{GameRessource, LoadError};
 
 use super::{
+    boss::Boss,
     enemy::Enemy,
     error_message::ErrorMessage,
+    game_event::GameEvent,
     game_settings::GameSettings,
+    gravity_well::GravityWell,
+    id_generator::IdGenerator,
     positioned::{Moves, Positioned},
-    projectile::Projectile,
+    power_up::{Effect, PowerUp, PowerUpKind},
+    projectile::{Faction, Projectile},
     shop::Shop,
+    snapshot::GameSnapshot,
+    spatial_hash::SpatialHash,
+    steering::{self, Behaviour},
+    wave::Wave,
 };
 
+/// The random number generator used by the game.
+///
+/// Every random decision of the simulation draws from this generator, so a game is fully determined by its seed and the actions taken.
+pub type GameRng = ChaCha8Rng;
+
+/// Returns a random seed for a new game.
+pub fn random_seed() -> u64 {
+    thread_rng().gen()
+}
+
+/// The number of ticks the game is calculated with per second.
+pub const TICKS_PER_SECOND: u32 = 50;
+
+/// The time in seconds that passes in the game with every tick.
+///
+/// The game always advances by this fixed amount, no matter how often the frontend renders. All timers are given in seconds and all speeds in pixels per second.
+pub const TICK_DURATION: f32 = 1.0 / TICKS_PER_SECOND as f32;
+
 /// The model of the game. It contains all the information about the game state.
 pub struct Game {
     /// The id used to save the game
     pub id: Option<u32>,
+    /// The seed the random number generator of this game was created with
+    pub seed: u64,
+    /// The random number generator of the game. Every spawn, split and speed roll draws from it.
+    rng: GameRng,
     /// The id generator is used to generate unique ids for game objects
-    id_generator: SequenceGenerator,
+    id_generator: IdGenerator,
     /// The shop contains all the items that can be bought
     pub shop: Shop,
     /// The game settings contain all the settings of the game
@@ -32,39 +69,66 @@ pub struct Game {
     pub coins: i32,
     /// The lives the player has left. If this is 0, the game is over. Starts with 3.
     pub lives: i32,
-    /// The time until the next shot can be fired. This time is configured by the [game settings](GameSettings).
-    pub time_until_next_shot: i32,
-    /// The time until the ship is vulnerable again. This time is configured by the [game settings](GameSettings).
-    pub time_until_vulnerable: i32,
-    /// The time until the next enemy is spawned. This time decreases with the score.
-    pub time_until_enemy_spawn: i32,
+    /// The time in seconds until the next shot can be fired. This time is configured by the [game settings](GameSettings).
+    pub time_until_next_shot: f32,
+    /// The time in seconds until the ship is vulnerable again. This time is configured by the [game settings](GameSettings).
+    pub time_until_vulnerable: f32,
+    /// The time in seconds until the next enemy is spawned. This time decreases with the score.
+    pub time_until_enemy_spawn: f32,
     /// The state of the game.
     pub state: GameState,
     /// The enemies in the game
     pub enemies: Vec<Enemy>,
     /// The projectiles in the game
     pub projectiles: Vec<Projectile>,
+    /// The gravity wells on the field. They appear as the score increases.
+    pub gravity_wells: Vec<GravityWell>,
+    /// The boss on the field. While it is there no enemies spawn.
+    pub boss: Option<Boss>,
+    /// The index of the next [boss score](GameSettings::boss_scores) to reach
+    pub next_boss: usize,
+    /// The current wave. Its enemies spawn one burst after the other
+    pub wave: Wave,
+    /// The time in seconds until the next wave starts. This only runs during the [intermission](GameState::Intermission)
+    pub time_until_next_wave: f32,
+    /// The power-ups drifting through the field
+    pub power_ups: Vec<PowerUp>,
+    /// The effects of the collected timed power-ups that are still active
+    pub effects: Vec<Effect>,
+    /// The events emitted since they were last [taken](Game::take_events)
+    events: Vec<GameEvent>,
+    /// The snapshots of the last frames. The newest snapshot is at the back.
+    history: VecDeque<GameSnapshot>,
+    /// The snapshots of the frames that were rewound. The next frame is at the back.
+    future: Vec<GameSnapshot>,
+    /// The maximum number of frames that are kept in the history. 0 disables the history.
+    history_depth: usize,
 }
 
 /// The state of the game
 #[wasm_bindgen]
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum GameState {
     Running,
     Paused,
     /// The game is not running. This is the initial state and the state when the game is over.
     NotRunning,
+    /// The last wave was cleared. The game waits for the next wave, so the player can shop in peace.
+    Intermission,
 }
 
 /// The actions that can be performed on the game
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum GameAction {
-    /// A tick is a frame of the game. This is used to calculate the next frame.
+    /// A tick is a frame of the game. This is used to calculate the next frame, which is [TICK_DURATION] seconds later.
     Tick,
     /// The ship shoots a projectile
     Shoot,
     /// The ship rotates. The parameters are the x and y coordinates of the mouse.
     RotateShip(f32, f32),
-    /// The game is started
+    /// The engine of the ship is started or stopped. This only has an effect if the ship can move freely.
+    Thrust(bool),
+    /// The game is started. During the [intermission](GameState::Intermission) this starts the next wave right away
     Start,
     /// The game is paused
     Pause,
@@ -74,43 +138,233 @@ pub enum GameAction {
     End,
 }
 
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Game {
-    /// Creates a new game
+    /// Creates a new game with a random seed
     pub fn new() -> Self {
+        Self::new_with_seed(random_seed())
+    }
+
+    /// Creates a new game whose random number generator is seeded with the given seed.
+    ///
+    /// Two games with the same seed play out the same way as long as they receive the same actions.
+    pub fn new_with_seed(seed: u64) -> Self {
         let game_settings = GameSettings::new();
         Self {
             id: None,
+            seed,
+            rng: GameRng::seed_from_u64(seed),
             shop: Shop::new(),
-            id_generator: SequenceGenerator::default(),
-            lives: game_settings.max_lives.clone(),
+            id_generator: IdGenerator::default(),
+            lives: game_settings.max_lives,
             game_settings,
             score: 0.0,
             coins: 0,
-            time_until_vulnerable: 0,
-            time_until_next_shot: 0,
-            time_until_enemy_spawn: 0,
+            time_until_vulnerable: 0.0,
+            time_until_next_shot: 0.0,
+            time_until_enemy_spawn: 0.0,
             state: GameState::NotRunning,
             enemies: vec![],
             projectiles: vec![],
+            gravity_wells: vec![],
+            boss: None,
+            next_boss: 0,
+            wave: Wave::new(1),
+            time_until_next_wave: 0.0,
+            power_ups: vec![],
+            effects: vec![],
+            events: vec![],
+            history: VecDeque::new(),
+            future: vec![],
+            history_depth: 0,
         }
     }
 
     /// Creates a new game from a game ressource (used to load an old game state from the api)
-    pub fn load(game_ressource: GameRessource) -> Self {
+    ///
+    /// If the ressource contains a snapshot, the game is restored from it. Otherwise only the score, coins, lives and items are restored.
+    ///
+    /// The ressource is validated first. An error is returned if it doesn't describe a reachable game.
+    pub fn load(game_ressource: GameRessource) -> Result<Self, LoadError> {
+        if let Some(snapshot) = &game_ressource.snapshot {
+            let snapshot = GameSnapshot::from_json(snapshot).map_err(|_| LoadError::Malformed)?;
+            snapshot.validate()?;
+
+            let mut game = Self::from_snapshot(snapshot);
+            game.id = game_ressource.id;
+            if game.state == GameState::Running {
+                game.state = GameState::Paused;
+            }
+            return Ok(game);
+        }
+
         let mut game = Self::new();
+        game_ressource.validate(game.game_settings.max_lives)?;
         game.state = GameState::Paused;
         game.id = game_ressource.id;
         game.score = game_ressource.score;
         game.coins = game_ressource.coins;
         game.lives = game_ressource.lives;
         game.game_settings.enemy_spawn_timeout = game_ressource.enemy_spawn_timeout;
-        game_ressource.items.into_iter().for_each(|item_level| {
+        for item_level in game_ressource.items {
             for _ in 0..item_level.level {
+                // Can only fail if the same item is listed more than once
                 game.step(GameAction::BuyItem(item_level.item.id, true))
-                    .expect("Error loading item");
+                    .map_err(|_| LoadError::LevelTooHigh(item_level.item.id))?;
             }
-        });
-        game
+        }
+        // Restoring the items is not part of the game
+        game.events.clear();
+        Ok(game)
+    }
+
+    /// Creates a snapshot of the complete state of the game.
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            id: self.id,
+            seed: self.seed,
+            rng: self.rng.clone(),
+            id_generator: self.id_generator.clone(),
+            game_settings: self.game_settings.clone(),
+            item_levels: self
+                .shop
+                .items
+                .iter()
+                .map(|item| (item.id, item.level))
+                .collect(),
+            score: self.score,
+            coins: self.coins,
+            lives: self.lives,
+            time_until_next_shot: self.time_until_next_shot,
+            time_until_vulnerable: self.time_until_vulnerable,
+            time_until_enemy_spawn: self.time_until_enemy_spawn,
+            state: self.state,
+            enemies: self.enemies.clone(),
+            gravity_wells: self.gravity_wells.clone(),
+            boss: self.boss.clone(),
+            next_boss: self.next_boss,
+            wave: self.wave.clone(),
+            time_until_next_wave: self.time_until_next_wave,
+            power_ups: self.power_ups.clone(),
+            effects: self.effects.clone(),
+            projectiles: self.projectiles.clone(),
+        }
+    }
+
+    /// Creates a game from a snapshot. The game continues exactly where the snapshot was taken.
+    pub fn from_snapshot(snapshot: GameSnapshot) -> Self {
+        let mut shop = Shop::new();
+        for (id, level) in snapshot.item_levels {
+            if let Some(item) = shop.items.iter_mut().find(|item| item.id == id) {
+                // The effects of the items are already part of the game settings
+                item.level = level;
+            }
+        }
+
+        Self {
+            id: snapshot.id,
+            seed: snapshot.seed,
+            rng: snapshot.rng,
+            id_generator: snapshot.id_generator,
+            shop,
+            game_settings: snapshot.game_settings,
+            score: snapshot.score,
+            coins: snapshot.coins,
+            lives: snapshot.lives,
+            time_until_next_shot: snapshot.time_until_next_shot,
+            time_until_vulnerable: snapshot.time_until_vulnerable,
+            time_until_enemy_spawn: snapshot.time_until_enemy_spawn,
+            state: snapshot.state,
+            enemies: snapshot.enemies,
+            projectiles: snapshot.projectiles,
+            gravity_wells: snapshot.gravity_wells,
+            boss: snapshot.boss,
+            next_boss: snapshot.next_boss,
+            wave: snapshot.wave,
+            time_until_next_wave: snapshot.time_until_next_wave,
+            power_ups: snapshot.power_ups,
+            effects: snapshot.effects,
+            events: vec![],
+            history: VecDeque::new(),
+            future: vec![],
+            history_depth: 0,
+        }
+    }
+
+    /// Sets the number of frames that are kept to rewind the game. 0 disables rewinding.
+    pub fn set_history_depth(&mut self, depth: usize) {
+        self.history_depth = depth;
+        while self.history.len() > depth {
+            self.history.pop_front();
+        }
+    }
+
+    /// Rewinds the game by up to the given number of frames. The game is paused afterwards.
+    ///
+    /// Returns the number of frames that were actually rewound.
+    pub fn rewind(&mut self, ticks: usize) -> usize {
+        let mut rewound = 0;
+        while rewound < ticks {
+            let snapshot = match self.history.pop_back() {
+                Some(snapshot) => snapshot,
+                None => break,
+            };
+            self.future.push(self.snapshot());
+            self.restore(snapshot);
+            rewound += 1;
+        }
+        rewound
+    }
+
+    /// Steps forward to the next frame that was rewound. The game is paused afterwards.
+    ///
+    /// Returns false if there is no rewound frame to step forward to.
+    pub fn step_forward(&mut self) -> bool {
+        match self.future.pop() {
+            Some(snapshot) => {
+                self.history.push_back(self.snapshot());
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the state of the game with a snapshot while keeping the history.
+    fn restore(&mut self, snapshot: GameSnapshot) {
+        let history = std::mem::take(&mut self.history);
+        let future = std::mem::take(&mut self.future);
+        let history_depth = self.history_depth;
+
+        *self = Self::from_snapshot(snapshot);
+        self.history = history;
+        self.future = future;
+        self.history_depth = history_depth;
+        if self.state == GameState::Running {
+            self.state = GameState::Paused;
+        }
+    }
+
+    /// Saves the current frame to the history. A new frame starts a new timeline, so the rewound frames are dropped.
+    fn push_history(&mut self) {
+        self.drop_future();
+        if self.history_depth == 0 {
+            return;
+        }
+        if self.history.len() >= self.history_depth {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.snapshot());
+    }
+
+    /// Drops the rewound frames, because the game takes a different turn than it did before the rewind.
+    fn drop_future(&mut self) {
+        self.future.clear();
     }
 
     /// Calculates a new frame of the game
@@ -119,75 +373,195 @@ impl Game {
     pub fn step(&mut self, action: GameAction) -> Result<(), ErrorMessage> {
         match (&self.state, action) {
             (GameState::Running, GameAction::Tick) => {
+                self.push_history();
+
                 // increase score
-                self.score += self.game_settings.score_increase;
+                self.score += self.game_settings.score_increase * TICK_DURATION as f64;
 
                 // decrease shoot timeout if necessary
-                if self.time_until_next_shot > 0 {
-                    self.time_until_next_shot -= 1;
+                if self.time_until_next_shot > 0.0 {
+                    self.time_until_next_shot -= TICK_DURATION;
                 }
 
                 // decrease invulnerability time if necessary
-                if self.time_until_vulnerable > 0 {
-                    self.time_until_vulnerable -= 1;
+                if self.time_until_vulnerable > 0.0 {
+                    self.time_until_vulnerable -= TICK_DURATION;
                 }
 
+                // revert the effects of power-ups that ran out
+                self.expire_effects();
+
                 // check for collisions
 
                 // check for collisions between projectiles and enemies
                 let new_enemies = self.collisions_enemy_projectile();
                 self.enemies = new_enemies;
 
+                // collect the power-ups the ship touched or shot
+                self.collisions_power_ups();
+
+                // bounce enemies off each other
+                self.collisions_enemy_enemy();
+
                 // check for collisions between enemies and ship
                 if let Some(e) = self.collision_enemy_ship() {
-                    if !self.is_invulnerable() {
-                        self.time_until_vulnerable = self.game_settings.invulnerability_time;
-                        self.lives -= 1;
-                        if self.lives == 0 {
-                            self.state = GameState::NotRunning;
-                        }
-                    }
+                    self.events.push(GameEvent::enemy_hit(&e, &[]));
+                    self.hit_ship();
 
                     // remove enemy
                     self.enemies
                         .retain(|enemy| enemy.get_position() != e.get_position());
                 }
 
-                // increase the enemy spawn rate every 100 points
+                // check for collisions between projectiles and the boss
+                self.collisions_boss_projectile();
+
+                // check for collisions between hostile projectiles and ship
+                self.collisions_projectile_ship();
+
+                // check for collisions between the boss and the ship
+                if let Some(boss) = &self.boss {
+                    if boss.is_collision(&self.game_settings.ship, &self.game_settings.field) {
+                        self.hit_ship();
+                    }
+                }
+
+                // increase the enemy spawn rate every 100 points (the timeout shrinks by about one tick each time)
                 if self.score.round() as i32 % 100 == 0
-                    && self.game_settings.enemy_spawn_timeout > 1.0
+                    && self.game_settings.enemy_spawn_timeout > TICK_DURATION
                 {
                     self.game_settings.enemy_spawn_timeout -=
-                        self.game_settings.score_increase as f32;
+                        self.game_settings.score_increase as f32 * TICK_DURATION * TICK_DURATION;
                 }
 
-                // spawn new enemies
-                if self.time_until_enemy_spawn <= 0 {
-                    self.time_until_enemy_spawn = self.game_settings.enemy_spawn_timeout as i32;
-                    self.enemies.append(&mut self.spawn_enemies());
+                // a boss appears when the score reaches the next boss score
+                self.spawn_boss();
+
+                // spawn new enemies, unless a boss is on the field
+                if self.boss.is_some() {
+                    // The spawn timer waits for the boss to be defeated
+                } else if self.time_until_enemy_spawn <= 0.0 {
+                    self.time_until_enemy_spawn = self.game_settings.enemy_spawn_timeout;
+                    let mut new_enemies = self.spawn_enemies();
+                    self.enemies.append(&mut new_enemies);
                 } else {
-                    self.time_until_enemy_spawn -= 1;
+                    self.time_until_enemy_spawn -= TICK_DURATION;
+                }
+
+                // spawn a new gravity well every time the score passes the interval
+                self.spawn_gravity_well();
+
+                let field = self.game_settings.field;
+
+                // Let enemy ships follow the ship
+                for enemy in &mut self.enemies {
+                    steering::steer(enemy, &self.game_settings);
+                }
+
+                // Let swarms flock together
+                steering::flock(&mut self.enemies, &self.game_settings);
+
+                // Let enemy ships shoot at the ship
+                self.shoot_enemy_projectiles();
+
+                // Let the boss attack
+                if let Some(boss) = &mut self.boss {
+                    for projectile in boss.attack(&self.game_settings, &self.id_generator) {
+                        self.events.push(GameEvent::ProjectileFired {
+                            id: projectile.id,
+                            faction: projectile.faction,
+                        });
+                        self.projectiles.push(projectile);
+                    }
                 }
 
-                let field = self.game_settings.field.clone();
+                // Bend the paths of enemies and projectiles towards the gravity wells
+                for well in &self.gravity_wells {
+                    self.projectiles
+                        .iter_mut()
+                        .for_each(|p| well.pull(p, &field));
+                    self.enemies.iter_mut().for_each(|e| well.pull(e, &field));
+                }
 
-                // Move projectiles and remove out of bounds projectiles and projectiles that have no hits left
-                self.projectiles
-                    .retain_mut(|p| p.move_tick(&field) && p.hits > 0);
+                // Move projectiles and remove out of bounds projectiles and projectiles that have no hits or lifetime left
+                self.projectiles.retain_mut(|p| {
+                    p.lifetime -= TICK_DURATION;
+                    p.move_tick(&field) && p.hits > 0 && p.lifetime > 0.0
+                });
 
                 // Move enemies and remove out of bounds enemies
                 self.enemies.retain_mut(|e| e.move_tick(&field));
+
+                // Move power-ups and remove those that left the field or weren't collected in time
+                self.power_ups.retain_mut(|p| {
+                    p.lifetime -= TICK_DURATION;
+                    p.move_tick(&field) && p.lifetime > 0.0
+                });
+
+                // Move the boss
+                if let Some(boss) = &mut self.boss {
+                    boss.fly(&self.game_settings);
+                }
+
+                // Move the ship if it can move freely
+                if let Some(movement) = self.game_settings.ship_movement {
+                    self.game_settings.ship.move_tick(&movement, &field);
+                }
+
+                // Destroy everything that crossed an event horizon
+                self.collisions_gravity_wells();
+
+                // The wave is cleared once all of its enemies were spawned and destroyed
+                if self.state == GameState::Running
+                    && self.wave.is_spawned()
+                    && self.enemies.is_empty()
+                    && self.boss.is_none()
+                {
+                    self.events.push(GameEvent::WaveCleared {
+                        number: self.wave.number,
+                    });
+                    self.state = GameState::Intermission;
+                    self.time_until_next_wave = self.game_settings.intermission_time;
+                }
+            }
+            (GameState::Intermission, GameAction::Tick) => {
+                self.time_until_next_wave -= TICK_DURATION;
+                if self.time_until_next_wave <= 0.0 {
+                    self.start_next_wave();
+                }
+            }
+            (GameState::Intermission, GameAction::Start) => {
+                self.start_next_wave();
             }
             (GameState::Running, GameAction::Shoot) => {
-                if self.time_until_next_shot <= 0 {
-                    self.time_until_next_shot = self.game_settings.shoot_timeout;
-                    self.projectiles.push(Projectile::new(self));
+                if self.time_until_next_shot <= 0.0 {
+                    self.drop_future();
+                    self.time_until_next_shot =
+                        Effect::shoot_timeout(&self.effects, &self.game_settings);
+                    for projectile in Projectile::volley(self) {
+                        self.events.push(GameEvent::ProjectileFired {
+                            id: projectile.id,
+                            faction: projectile.faction,
+                        });
+                        self.projectiles.push(projectile);
+                    }
                 }
             }
             (GameState::Running, GameAction::RotateShip(dx, dy)) => {
+                self.drop_future();
                 self.game_settings.ship.dx = dx;
                 self.game_settings.ship.dy = dy;
             }
+            // The engine can be stopped while the game is paused, so it doesn't keep running after the game is resumed
+            (
+                GameState::Running | GameState::Paused | GameState::Intermission,
+                GameAction::Thrust(thrusting),
+            ) => {
+                if self.game_settings.ship.thrusting != thrusting {
+                    self.drop_future();
+                    self.game_settings.ship.thrusting = thrusting;
+                }
+            }
             (GameState::Paused, GameAction::Start) => {
                 self.state = GameState::Running;
             }
@@ -196,9 +570,12 @@ impl Game {
             }
             (GameState::NotRunning, GameAction::Start) => {
                 self.state = GameState::Running;
+                self.events.push(GameEvent::WaveStarted {
+                    number: self.wave.number,
+                });
             }
             (
-                GameState::Running | GameState::Paused,
+                GameState::Running | GameState::Paused | GameState::Intermission,
                 GameAction::BuyItem(item_id, ignore_coins),
             ) => {
                 let item = self.shop.buy_item(item_id, self.coins, ignore_coins);
@@ -209,15 +586,21 @@ impl Game {
                             self.coins -= item.get_current_price();
                         }
                         (item.apply_item)(&mut self.game_settings, item.level);
+                        self.events.push(GameEvent::ItemBought {
+                            item_id,
+                            level: item.level,
+                        });
+                        self.drop_future();
                     }
                     Err(err) => {
                         return Err(err);
                     }
                 }
             }
-            (GameState::Running | GameState::Paused, GameAction::End) => {
+            (GameState::Running | GameState::Paused | GameState::Intermission, GameAction::End) => {
+                self.drop_future();
                 self.lives = 0;
-                self.state = GameState::NotRunning;
+                self.end();
             }
 
             (GameState::Paused, GameAction::RotateShip(_, _)) => (),
@@ -226,69 +609,425 @@ impl Game {
             (GameState::NotRunning, GameAction::Tick) => (),
             (GameState::NotRunning, GameAction::Shoot) => (),
             (GameState::NotRunning, GameAction::RotateShip(_, _)) => (),
+            (GameState::NotRunning, GameAction::Thrust(_)) => (),
             (GameState::Running, GameAction::Start) => (),
             (GameState::Paused, GameAction::Pause) => (),
             (GameState::NotRunning, GameAction::Pause) => (),
             (GameState::NotRunning, GameAction::BuyItem(_, _)) => (),
             (GameState::NotRunning, GameAction::End) => (),
+            (GameState::Intermission, GameAction::Shoot) => (),
+            (GameState::Intermission, GameAction::RotateShip(_, _)) => (),
+            // The intermission already is a break
+            (GameState::Intermission, GameAction::Pause) => (),
         };
         Ok(())
     }
 
     /// Returns the vec of enemies to be spawned in the next frame.
     ///
-    /// The higher the score, the more enemies are spawned.
-    fn spawn_enemies(&self) -> Vec<Enemy> {
-        let mut new_enemies = vec![];
+    /// These are the next [burst](crate::wave::WaveGroup::burst) of enemies of the current wave. Once all enemies of the wave were spawned no more enemies are spawned.
+    /// A burst of swarming enemies [joins](Enemy::join) its first enemy, so the swarm enters the field together.
+    fn spawn_enemies(&mut self) -> Vec<Enemy> {
+        let (group, count) = match self.wave.next_burst() {
+            Some(burst) => burst,
+            None => return vec![],
+        };
 
-        let enemy = Enemy::new(&self.game_settings, self.score, self.get_next_id());
-        new_enemies.push(enemy);
+        let mut enemies: Vec<Enemy> = (0..count)
+            .map(|_| {
+                Enemy::from_group(
+                    &self.game_settings,
+                    &group,
+                    self.score,
+                    self.id_generator.next_id(),
+                    &mut self.rng,
+                )
+            })
+            .collect();
+        if let Some((leader, swarm)) = enemies.split_first_mut() {
+            if leader.swarms(&self.game_settings) {
+                for enemy in swarm {
+                    enemy.join(leader, &self.game_settings, &mut self.rng);
+                }
+            }
+        }
+        enemies
+    }
 
-        new_enemies
+    /// Ends the intermission and starts the next wave. Its first enemies spawn right away.
+    fn start_next_wave(&mut self) {
+        self.wave = Wave::new(self.wave.number + 1);
+        self.time_until_next_wave = 0.0;
+        self.time_until_enemy_spawn = 0.0;
+        self.state = GameState::Running;
+        self.events.push(GameEvent::WaveStarted {
+            number: self.wave.number,
+        });
     }
 
-    /// Checks for collisions between enemies and projectiles.  
+    /// Checks for collisions between enemies and projectiles.
     ///
     /// Returns the enemies that are still alive.
     ///
-    /// TODO: This is a very naive implementation. It checks every projectile against every enemy.
-    /// This is very inefficient.
+    /// The projectiles are inserted into a [spatial hash](SpatialHash), so every enemy is only checked against the projectiles near it.
+    /// Only projectiles of the player hit enemies. An enemy is hit by the first of those projectiles whose path in the last tick crosses it, so fast projectiles can't tunnel through small enemies.
+    /// A projectile hits at most as many enemies as it has [hits](Projectile::hits) left, so only piercing projectiles cut through a swarm.
+    /// Enemies that are destroyed or split may [drop a power-up](Game::drop_power_up).
     fn collisions_enemy_projectile(&mut self) -> Vec<Enemy> {
         let mut new_enemies: Vec<Enemy> = vec![];
-        let mut hits = 0;
+        let mut destroyed = vec![];
+        let mut dropped_coins = 0;
 
         if self.projectiles.is_empty() {
             return self.enemies.clone();
         }
 
+        let field = &self.game_settings.field;
+        let grid = SpatialHash::from_moving_objects(field, &self.projectiles);
+        let mut candidates = vec![];
+
         for enemy in &self.enemies {
-            let projectile = self.projectiles.iter_mut().find(|p| enemy.is_collision(*p));
+            grid.query_into(enemy, &mut candidates);
+            let projectile = candidates.iter().copied().find(|&i| {
+                self.projectiles[i].faction == Faction::Player
+                    && self.projectiles[i].hits > 0
+                    && self.projectiles[i].is_swept_collision(enemy, field)
+            });
 
-            if let Some(p) = projectile {
-                new_enemies.extend(enemy.take_damage(
+            if let Some(i) = projectile {
+                let p = &mut self.projectiles[i];
+                let remains = enemy.take_damage(
                     &self.game_settings,
                     p.damage,
-                    self.id_generator.clone(),
-                ));
+                    self.score,
+                    &self.id_generator,
+                    &mut self.rng,
+                );
+                self.events.push(GameEvent::enemy_hit(enemy, &remains));
+                // Only enemies that split or are destroyed drop coins, so armored enemies don't pay for every hit
+                if !remains.iter().any(|e| e.id == enemy.id) {
+                    destroyed.push((enemy.get_position(), enemy.direction()));
+                    dropped_coins += enemy.get_coins(&self.game_settings);
+                }
+                new_enemies.extend(remains);
                 p.hits -= 1;
-                hits += enemy.get_coins();
             } else {
                 new_enemies.push(enemy.clone());
             }
         }
 
         // increase coins
-        self.coins += hits * self.game_settings.coin_multiplier;
+        let coins = dropped_coins * self.game_settings.coin_multiplier;
+        if coins > 0 {
+            self.coins += coins;
+            self.events.push(GameEvent::CoinsGained { amount: coins });
+        }
+
+        for (position, direction) in destroyed {
+            self.drop_power_up(position, direction);
+        }
 
         new_enemies
     }
 
+    /// Drops a power-up where an enemy was destroyed with the [power-up probability](GameSettings::power_up_probability).
+    fn drop_power_up(&mut self, position: (f32, f32), direction: (f32, f32)) {
+        if self.rng.gen::<f32>() >= self.game_settings.power_up_probability {
+            return;
+        }
+        let power_up = PowerUp::drop_at(
+            position,
+            direction,
+            &self.game_settings,
+            self.id_generator.next_id(),
+            &mut self.rng,
+        );
+        self.events.push(GameEvent::PowerUpDropped {
+            id: power_up.id,
+            kind: power_up.kind,
+            x: power_up.x,
+            y: power_up.y,
+        });
+        self.power_ups.push(power_up);
+    }
+
+    /// Collects the power-ups that touch the ship or are hit by a projectile of the player. Every projectile collects one power-up.
+    fn collisions_power_ups(&mut self) {
+        let field = &self.game_settings.field;
+        let ship = &self.game_settings.ship;
+        let mut collected = vec![];
+        for power_up in std::mem::take(&mut self.power_ups) {
+            let projectile = self.projectiles.iter_mut().find(|p| {
+                p.faction == Faction::Player && p.hits > 0 && p.is_swept_collision(&power_up, field)
+            });
+            if let Some(p) = projectile {
+                p.hits -= 1;
+                collected.push(power_up);
+            } else if ship.is_collision(&power_up, field) {
+                collected.push(power_up);
+            } else {
+                self.power_ups.push(power_up);
+            }
+        }
+
+        for power_up in collected {
+            self.events.push(GameEvent::PowerUpCollected {
+                id: power_up.id,
+                kind: power_up.kind,
+            });
+            self.collect_power_up(power_up.kind);
+        }
+    }
+
+    /// Applies a collected power-up.
+    ///
+    /// Collecting a timed power-up whose effect is still active restarts the effect instead of applying it twice.
+    fn collect_power_up(&mut self, kind: PowerUpKind) {
+        match kind {
+            PowerUpKind::ExtraLife => {
+                self.lives = (self.lives + 1).min(self.game_settings.max_lives);
+            }
+            PowerUpKind::CoinBurst => {
+                let coins = self.game_settings.power_up_coins * self.game_settings.coin_multiplier;
+                self.coins += coins;
+                self.events.push(GameEvent::CoinsGained { amount: coins });
+            }
+            _ => match self.effects.iter_mut().find(|effect| effect.kind == kind) {
+                Some(effect) => effect.time_left = self.game_settings.power_up_duration,
+                None => self
+                    .effects
+                    .push(Effect::apply(kind, &mut self.game_settings)),
+            },
+        }
+    }
+
+    /// Lets the effects of power-ups run for one tick and reverts those that ran out.
+    fn expire_effects(&mut self) {
+        let settings = &mut self.game_settings;
+        let events = &mut self.events;
+        self.effects.retain_mut(|effect| {
+            let active = effect.tick();
+            if !active {
+                effect.revert(settings);
+                events.push(GameEvent::PowerUpExpired { kind: effect.kind });
+            }
+            active
+        });
+    }
+
+    /// Resolves collisions between enemies if [enemy collisions](GameSettings::enemy_collisions) are enabled.
+    ///
+    /// Every pair of colliding enemies [bounces](Enemy::bounce) off each other once per tick.
+    fn collisions_enemy_enemy(&mut self) {
+        if !self.game_settings.enemy_collisions {
+            return;
+        }
+
+        let field = &self.game_settings.field;
+        let grid = SpatialHash::from_objects(field, &self.enemies);
+        let mut candidates = vec![];
+        for i in 0..self.enemies.len() {
+            grid.query_into(&self.enemies[i], &mut candidates);
+            for &j in candidates.iter().filter(|&&j| j > i) {
+                let (left, right) = self.enemies.split_at_mut(j);
+                left[i].bounce(&mut right[0], self.game_settings.enemy_restitution, field);
+            }
+        }
+    }
+
+    /// Lets every enemy ship that is after the ship shoot at it once its [timeout](GameSettings::enemy_shoot_timeout) ran out.
+    ///
+    /// Fleeing enemy ships don't shoot.
+    fn shoot_enemy_projectiles(&mut self) {
+        let timeout = self.game_settings.enemy_shoot_timeout(self.score);
+        for enemy in &mut self.enemies {
+            if !enemy.hunts(&self.game_settings) {
+                continue;
+            }
+            if enemy.time_until_next_shot > 0.0 {
+                enemy.time_until_next_shot -= TICK_DURATION;
+                continue;
+            }
+            if !matches!(
+                enemy.behaviour,
+                Behaviour::Seek | Behaviour::Pursue | Behaviour::Orbit
+            ) {
+                continue;
+            }
+
+            enemy.time_until_next_shot = timeout;
+            let projectile =
+                Projectile::hostile(enemy, &self.game_settings, self.id_generator.next_id());
+            self.events.push(GameEvent::ProjectileFired {
+                id: projectile.id,
+                faction: projectile.faction,
+            });
+            self.projectiles.push(projectile);
+        }
+    }
+
+    /// Checks for collisions between projectiles of the player and the boss. Every projectile [damages](Boss::damage_by) the boss once.
+    ///
+    /// The boss emits an event whenever it enters the next phase. A defeated boss is removed and pays out its [coins](GameSettings::boss_coins).
+    fn collisions_boss_projectile(&mut self) {
+        let boss = match &mut self.boss {
+            Some(boss) => boss,
+            None => return,
+        };
+        let field = &self.game_settings.field;
+        let phase = boss.phase();
+        for p in &mut self.projectiles {
+            if p.faction != Faction::Player || p.hits <= 0 {
+                continue;
+            }
+            if let Some(damage) = boss.damage_by(p, field) {
+                boss.health -= damage;
+                p.hits -= 1;
+            }
+        }
+
+        if boss.health <= 0 {
+            let coins = self.game_settings.boss_coins * self.game_settings.coin_multiplier;
+            self.events.push(GameEvent::BossDefeated {
+                id: boss.id,
+                x: boss.x,
+                y: boss.y,
+                coins,
+            });
+            self.boss = None;
+            self.coins += coins;
+            self.events.push(GameEvent::CoinsGained { amount: coins });
+        } else if boss.phase() != phase {
+            self.events.push(GameEvent::BossPhaseChanged {
+                id: boss.id,
+                phase: boss.phase(),
+            });
+        }
+    }
+
+    /// Spawns the next boss if the score reached its [boss score](GameSettings::boss_scores) and no boss is on the field.
+    fn spawn_boss(&mut self) {
+        if self.boss.is_some() {
+            return;
+        }
+        let due = match self.game_settings.boss_scores.get(self.next_boss) {
+            Some(&score) => self.score >= score,
+            None => false,
+        };
+        if due {
+            let boss = Boss::new(
+                &self.game_settings,
+                self.next_boss,
+                self.id_generator.next_id(),
+            );
+            self.events.push(GameEvent::BossSpawned {
+                id: boss.id,
+                max_health: boss.max_health,
+            });
+            self.boss = Some(boss);
+            self.next_boss += 1;
+        }
+    }
+
+    /// Checks for collisions between hostile projectiles and the ship. A projectile that hits the ship is destroyed, even if the ship is invulnerable.
+    fn collisions_projectile_ship(&mut self) {
+        let ship = &self.game_settings.ship;
+        let field = &self.game_settings.field;
+        let mut hit = false;
+        for p in &mut self.projectiles {
+            if p.faction == Faction::Hostile && p.hits > 0 && p.is_swept_collision(ship, field) {
+                p.hits = 0;
+                hit = true;
+            }
+        }
+        if hit {
+            self.hit_ship();
+        }
+    }
+
     /// Checks for collisions between enemies and the ship.
+    ///
+    /// Returns the first enemy that collides with the ship.
     fn collision_enemy_ship(&self) -> Option<Enemy> {
-        self.enemies
-            .iter()
-            .find(|e| self.game_settings.ship.is_collision(*e))
-            .cloned()
+        let ship = &self.game_settings.ship;
+        SpatialHash::from_objects(&self.game_settings.field, &self.enemies)
+            .query(ship)
+            .into_iter()
+            .map(|i| self.enemies[i].clone())
+            .find(|e| ship.is_collision(e, &self.game_settings.field))
+    }
+
+    /// Takes a life from the ship unless it is invulnerable. The ship is invulnerable for a while afterwards.
+    fn hit_ship(&mut self) {
+        if self.is_invulnerable() {
+            return;
+        }
+        self.time_until_vulnerable = self.game_settings.invulnerability_time;
+        self.lives -= 1;
+        self.events.push(GameEvent::ShipHit { lives: self.lives });
+        if self.lives == 0 {
+            self.end();
+        }
+    }
+
+    /// Ends the game.
+    fn end(&mut self) {
+        self.state = GameState::NotRunning;
+        self.events.push(GameEvent::GameOver { score: self.score });
+    }
+
+    /// Spawns a gravity well if the score passed the next multiple of the [gravity well interval](GameSettings::gravity_well_interval) and the field has room for another one.
+    fn spawn_gravity_well(&mut self) {
+        let settings = &self.game_settings;
+        if settings.gravity_well_interval <= 0.0 {
+            return;
+        }
+        let due = (self.score / settings.gravity_well_interval) as usize;
+        if self.gravity_wells.len() < due.min(settings.max_gravity_wells as usize) {
+            // There may be no place far enough away from the ship. The next tick tries again
+            if let Some(well) =
+                GravityWell::new(settings, self.id_generator.next_id(), &mut self.rng)
+            {
+                self.events.push(GameEvent::GravityWellSpawned {
+                    id: well.id,
+                    x: well.x,
+                    y: well.y,
+                });
+                self.gravity_wells.push(well);
+            }
+        }
+    }
+
+    /// Removes the enemies and projectiles that crossed the event horizon of a gravity well. Swallowed enemies don't give coins.
+    ///
+    /// The ship loses a life if it flies into a gravity well.
+    fn collisions_gravity_wells(&mut self) {
+        let field = &self.game_settings.field;
+        let wells = &self.gravity_wells;
+        let events = &mut self.events;
+        self.enemies.retain(|e| {
+            let swallowed = wells.iter().any(|well| well.swallows(e, field));
+            if swallowed {
+                events.push(GameEvent::EnemySwallowed {
+                    id: e.id,
+                    x: e.x,
+                    y: e.y,
+                });
+            }
+            !swallowed
+        });
+        self.projectiles
+            .retain(|p| !wells.iter().any(|well| well.swallows(p, field)));
+
+        let ship = &self.game_settings.ship;
+        if wells.iter().any(|well| well.swallows(ship, field)) {
+            self.hit_ship();
+        }
+    }
+
+    /// Returns the events emitted since the last call. The events are removed from the game.
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
     }
 
     /// Returns the next id for a game object.
@@ -296,8 +1035,8 @@ impl Game {
         self.id_generator.next_id()
     }
 
-    /// Returns true if the ship is invulnerable (e.g. after getting hit by an enemy).
+    /// Returns true if the ship is invulnerable (e.g. after getting hit by an enemy or while it is shielded).
     pub fn is_invulnerable(&self) -> bool {
-        !(self.time_until_vulnerable <= 0)
+        self.time_until_vulnerable > 0.0 || self.game_settings.ship_shielded
     }
 }
@@ -0,0 +1,105 @@
+//! This module contains the events the game emits while it is played.
+//!
+//! Every [step](super::game::Game::step) can emit events describing what happened in it. They are collected by the game until they are taken by the frontend, which uses them to drive sounds, particles and other feedback.
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::{enemy::Enemy, power_up::PowerUpKind, projectile::Faction};
+
+/// Something that happened in the game.
+#[derive(TS, Serialize, Clone, Debug, PartialEq)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    /// An enemy was destroyed by a projectile or by crashing into the ship
+    EnemyDestroyed {
+        id: i64,
+        enemy_type: String,
+        x: f32,
+        y: f32,
+    },
+    /// An enemy was hit and broke into the enemies with the ids in `children`
+    EnemySplit {
+        id: i64,
+        enemy_type: String,
+        x: f32,
+        y: f32,
+        children: Vec<i64>,
+    },
+    /// An enemy was hit but survived with the given health and armor
+    EnemyDamaged {
+        id: i64,
+        x: f32,
+        y: f32,
+        health: i32,
+        armor: i32,
+    },
+    /// An enemy crossed the event horizon of a gravity well
+    EnemySwallowed { id: i64, x: f32, y: f32 },
+    /// A new gravity well appeared on the field
+    GravityWellSpawned { id: i64, x: f32, y: f32 },
+    /// The ship or an enemy ship fired a projectile
+    ProjectileFired {
+        id: i64,
+        #[ts(type = "string")]
+        faction: Faction,
+    },
+    /// A boss appeared on the field
+    BossSpawned { id: i64, max_health: i32 },
+    /// A boss lost enough health to enter the next phase
+    BossPhaseChanged { id: i64, phase: u8 },
+    /// A boss was defeated and paid out the given coins
+    BossDefeated { id: i64, x: f32, y: f32, coins: i32 },
+    /// A wave started
+    WaveStarted { number: u32 },
+    /// All enemies of a wave were destroyed. The intermission starts
+    WaveCleared { number: u32 },
+    /// A destroyed enemy dropped a power-up
+    PowerUpDropped {
+        id: i64,
+        kind: PowerUpKind,
+        x: f32,
+        y: f32,
+    },
+    /// The ship collected a power-up by flying into it or shooting it
+    PowerUpCollected { id: i64, kind: PowerUpKind },
+    /// The effect of a timed power-up ran out and was reverted
+    PowerUpExpired { kind: PowerUpKind },
+    /// The ship lost a life. `lives` is the number of lives left
+    ShipHit { lives: i32 },
+    /// Coins were awarded for hitting enemies
+    CoinsGained { amount: i32 },
+    /// An item was bought and is now at the given level
+    ItemBought { item_id: u32, level: u8 },
+    /// The game ended with the given score
+    GameOver { score: f64 },
+}
+
+impl GameEvent {
+    /// Describes what happened to an enemy that was hit. `remains` are the enemies [taking the damage](Enemy::take_damage) resulted in.
+    pub fn enemy_hit(enemy: &Enemy, remains: &[Enemy]) -> Self {
+        match remains {
+            [] => GameEvent::EnemyDestroyed {
+                id: enemy.id,
+                enemy_type: enemy.enemy_type.clone(),
+                x: enemy.x,
+                y: enemy.y,
+            },
+            [damaged] if damaged.id == enemy.id => GameEvent::EnemyDamaged {
+                id: enemy.id,
+                x: enemy.x,
+                y: enemy.y,
+                health: damaged.health,
+                armor: damaged.armor,
+            },
+            children => GameEvent::EnemySplit {
+                id: enemy.id,
+                enemy_type: enemy.enemy_type.clone(),
+                x: enemy.x,
+                y: enemy.y,
+                children: children.iter().map(|child| child.id).collect(),
+            },
+        }
+    }
+}
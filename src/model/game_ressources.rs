@@ -1,12 +1,84 @@
 //! This module contains the representation of the game used by the api. This is used to save and load the game.
+//!
+//! Saves are versioned. Older saves coming back from the api are upgraded to the current shape by a chain of migrations before they are loaded.
+
+use std::convert::TryFrom;
 
 use serde::Deserialize;
+use serde_json::Value;
 use ts_rs::TS;
 
+use crate::error;
+
+use super::{
+    archetype::CatalogError,
+    error_message::ErrorMessage,
+    game::{TICKS_PER_SECOND, TICK_DURATION},
+    shop::Shop,
+};
+
+/// The version of the save format written by this version of the game.
+pub const SAVE_VERSION: u32 = 3;
+
+/// The migrations between the versions of the save format. The migration at index `i` upgrades a save from version `i + 1` to version `i + 2`.
+const MIGRATIONS: [fn(Value) -> Result<Value, LoadError>; (SAVE_VERSION - 1) as usize] =
+    [migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// The errors that can occur while loading a saved game.
+#[derive(Debug, PartialEq)]
+pub enum LoadError {
+    /// The save doesn't have the expected shape
+    Malformed,
+    /// The save was written by a newer version of the game
+    UnknownVersion(u32),
+    /// The save contains an item with the given id that doesn't exist
+    UnknownItem(u32),
+    /// The item with the given id has a level above its maximum level
+    LevelTooHigh(u32),
+    /// The save has a negative amount of coins
+    NegativeCoins,
+    /// The save has more lives than allowed or a negative amount of lives
+    InvalidLives,
+    /// The save contains an enemy whose archetype doesn't exist
+    UnknownArchetype(String),
+    /// The archetypes of the save can't be used
+    InvalidArchetypes(CatalogError),
+    /// The settings of the save don't match its items and active power-ups
+    InvalidSettings,
+}
+
+impl From<LoadError> for ErrorMessage {
+    fn from(load_error: LoadError) -> Self {
+        match load_error {
+            LoadError::Malformed => error!("The saved game is corrupted"),
+            LoadError::UnknownVersion(_) => {
+                error!("The saved game was created by a newer version of the game")
+            }
+            LoadError::UnknownItem(_) => error!("The saved game contains an unknown item"),
+            LoadError::LevelTooHigh(_) => {
+                error!("The saved game contains an item above its maximum level")
+            }
+            LoadError::NegativeCoins => error!("The saved game has a negative amount of coins"),
+            LoadError::InvalidLives => error!("The saved game has an invalid number of lives"),
+            LoadError::UnknownArchetype(_) => {
+                error!("The saved game contains an unknown kind of enemy")
+            }
+            LoadError::InvalidArchetypes(_) => {
+                error!("The saved game contains invalid kinds of enemies")
+            }
+            LoadError::InvalidSettings => {
+                error!("The saved game has settings that don't match its items")
+            }
+        }
+    }
+}
+
 /// The representation of the game used by the api. This is used to save and load the game.
 #[derive(TS, Deserialize)]
 #[ts(export)]
 pub struct GameRessource {
+    /// The version of the save format. See [SAVE_VERSION].
+    pub version: u32,
     pub id: Option<u32>,
     pub ended: bool,
     pub score: f64,
@@ -14,6 +86,164 @@ pub struct GameRessource {
     pub lives: i32,
     pub enemy_spawn_timeout: f32,
     pub items: Vec<ItemLevelRessource>,
+    /// The complete [snapshot](crate::snapshot::GameSnapshot) of the game as json. Older saves don't contain a snapshot.
+    #[serde(default)]
+    #[ts(optional)]
+    pub snapshot: Option<String>,
+}
+
+impl GameRessource {
+    /// Parses a saved game of any known version and upgrades it to the current version.
+    pub fn from_json(mut save: Value) -> Result<Self, LoadError> {
+        let version = save_version(&save)?;
+        if version == 0 || version > SAVE_VERSION {
+            return Err(LoadError::UnknownVersion(version));
+        }
+
+        for migration in &MIGRATIONS[(version - 1) as usize..] {
+            save = migration(save)?;
+        }
+
+        serde_json::from_value(save).map_err(|_| LoadError::Malformed)
+    }
+
+    /// Checks that the save describes a game that can actually be reached.
+    pub fn validate(&self, max_lives: i32) -> Result<(), LoadError> {
+        validate_resources(self.coins, self.lives, max_lives)?;
+        let shop = Shop::new();
+        for item_level in &self.items {
+            validate_item_level(&shop, item_level.item.id, item_level.level)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the coins are not negative and the lives are between 0 and the maximum number of lives.
+pub(crate) fn validate_resources(coins: i32, lives: i32, max_lives: i32) -> Result<(), LoadError> {
+    if coins < 0 {
+        return Err(LoadError::NegativeCoins);
+    }
+    if lives < 0 || lives > max_lives {
+        return Err(LoadError::InvalidLives);
+    }
+    Ok(())
+}
+
+/// Checks that the item exists in the shop and the level doesn't exceed its maximum level.
+pub(crate) fn validate_item_level(shop: &Shop, id: u32, level: u32) -> Result<(), LoadError> {
+    let item = shop
+        .items
+        .iter()
+        .find(|item| item.id == id)
+        .ok_or(LoadError::UnknownItem(id))?;
+    if level > item.max_level as u32 {
+        return Err(LoadError::LevelTooHigh(id));
+    }
+    Ok(())
+}
+
+/// Returns the version of a save. Saves written before the format was versioned don't have a version and are version 1.
+fn save_version(save: &Value) -> Result<u32, LoadError> {
+    match save.get("version") {
+        None | Some(Value::Null) => Ok(1),
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or(LoadError::Malformed),
+    }
+}
+
+/// Version 1 saves were written before the format was versioned and before games were saved with a snapshot.
+fn migrate_v1_to_v2(mut save: Value) -> Result<Value, LoadError> {
+    let save_object = save.as_object_mut().ok_or(LoadError::Malformed)?;
+    save_object.insert("version".to_string(), Value::from(2));
+    save_object.entry("snapshot").or_insert(Value::Null);
+    Ok(save)
+}
+
+/// Version 2 saves measured timers in ticks and speeds in pixels per tick. Since version 3 they are measured in seconds.
+fn migrate_v2_to_v3(mut save: Value) -> Result<Value, LoadError> {
+    let save_object = save.as_object_mut().ok_or(LoadError::Malformed)?;
+    save_object.insert("version".to_string(), Value::from(3));
+    scale(&mut save, "enemy_spawn_timeout", TICK_DURATION as f64)?;
+
+    if let Some(Value::String(snapshot)) = save.get_mut("snapshot") {
+        let mut snapshot_value: Value =
+            serde_json::from_str(snapshot).map_err(|_| LoadError::Malformed)?;
+        migrate_snapshot_v2_to_v3(&mut snapshot_value)?;
+        *snapshot = snapshot_value.to_string();
+    }
+    Ok(save)
+}
+
+/// Converts the timers and speeds of a version 2 snapshot to seconds.
+fn migrate_snapshot_v2_to_v3(snapshot: &mut Value) -> Result<(), LoadError> {
+    let ticks = TICKS_PER_SECOND as f64;
+    for timer in [
+        "time_until_next_shot",
+        "time_until_vulnerable",
+        "time_until_enemy_spawn",
+    ] {
+        scale(snapshot, timer, 1.0 / ticks)?;
+    }
+
+    let settings = snapshot
+        .get_mut("game_settings")
+        .ok_or(LoadError::Malformed)?;
+    for speed in [
+        "projectile_speed",
+        "enemy_speed_multiplier",
+        "enemy_min_speed",
+        "score_increase",
+    ] {
+        scale(settings, speed, ticks)?;
+    }
+    for timer in [
+        "shoot_timeout",
+        "invulnerability_time",
+        "enemy_spawn_timeout",
+        "projectile_lifetime",
+    ] {
+        scale(settings, timer, 1.0 / ticks)?;
+    }
+    if let Some(ship) = settings.get_mut("ship") {
+        scale(ship, "vx", ticks)?;
+        scale(ship, "vy", ticks)?;
+    }
+    if let Some(movement) = settings.get_mut("ship_movement").filter(|m| !m.is_null()) {
+        scale(movement, "thrust", ticks * ticks)?;
+        scale(movement, "max_speed", ticks)?;
+        // The ship lost the fraction per tick, so it keeps (1 - drag) to the power of the ticks per second
+        if let Some(drag) = movement.get_mut("drag") {
+            let per_tick = drag.as_f64().ok_or(LoadError::Malformed)?;
+            *drag = Value::from(1.0 - (1.0 - per_tick).powf(ticks));
+        }
+    }
+
+    for list in ["enemies", "projectiles"] {
+        let objects = snapshot
+            .get_mut(list)
+            .and_then(Value::as_array_mut)
+            .ok_or(LoadError::Malformed)?;
+        for object in objects {
+            scale(object, "dx", ticks)?;
+            scale(object, "dy", ticks)?;
+            scale(object, "lifetime", 1.0 / ticks)?;
+        }
+    }
+    Ok(())
+}
+
+/// Multiplies the number stored under the key by the factor. Missing keys are skipped, as they are filled with defaults when the save is loaded.
+fn scale(object: &mut Value, key: &str, factor: f64) -> Result<(), LoadError> {
+    match object.get_mut(key) {
+        None | Some(Value::Null) => Ok(()),
+        Some(value) => {
+            let number = value.as_f64().ok_or(LoadError::Malformed)?;
+            *value = Value::from(number * factor);
+            Ok(())
+        }
+    }
 }
 
 /// The representation of an item used by the api. This is used to save and load the game.
@@ -4,7 +4,14 @@
 //!
 //! This allows for the shop-items to just change those values.
 
-use self::{field::Field, ship::Ship};
+use serde::{Deserialize, Serialize};
+
+use super::archetype::Archetype;
+
+use self::{
+    field::Field,
+    ship::{Ship, ShipMovement},
+};
 
 pub mod field;
 pub mod ship;
@@ -14,36 +21,99 @@ pub mod ship;
 /// This struct contains all the settings of the game. It is used to configure the game.
 ///
 /// This allows for the shop-items to just change those values.
-#[derive(Clone)]
+///
+/// All timers are given in seconds and all speeds in pixels per second.
+///
+/// Settings saved before a field existed take its value from [new](GameSettings::new) games.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameSettings {
-    /// The speed of the projectiles
+    /// The speed of the projectiles in pixels per second
     pub projectile_speed: f32,
-    /// The probability that the enemy will be an asteroid. The higher the number the higher the chance that the enemy will be an asteroid
-    pub asteroid_probability: f32,
     /// The multiplier for the coins gained by destroying an enemy
     pub coin_multiplier: i32,
     /// The visibility of the ship. The lower the number the lower the chance the enemy will fly directly towards the ship. Number between 0 and 10.
     pub ship_visibility: f32,
-    /// The multiplier for the speed of the enemies
+    /// The multiplier for the speed of the enemies in pixels per second
     pub enemy_speed_multiplier: f32,
-    /// The timeout between shots. The lower the number the higher the shooting rate
-    pub shoot_timeout: i32,
-    /// The increase in score per frame
+    /// The timeout between shots in seconds. The lower the number the higher the shooting rate
+    pub shoot_timeout: f32,
+    /// The increase in score per second
     pub score_increase: f64,
     /// The maximum number of lives
     pub max_lives: i32,
-    /// The minimum speed of the enemies
+    /// The minimum speed of the enemies in pixels per second
     pub enemy_min_speed: f32,
     /// The field
     pub field: Field,
     /// The ship
     pub ship: Ship,
-    /// The time the ship is invulnerable after being hit
-    pub invulnerability_time: i32,
-    /// The timeout between enemy spawns. The lower the number the higher the spawn rate
+    /// How the ship flies. If this is none the ship stays in the middle of the field
+    pub ship_movement: Option<ShipMovement>,
+    /// The time in seconds the ship is invulnerable after being hit
+    pub invulnerability_time: f32,
+    /// The timeout between enemy spawns in seconds. The lower the number the higher the spawn rate
     pub enemy_spawn_timeout: f32,
     /// The number of enemies a projectile can hit before being destroyed
     pub projectile_hits: i32,
+    /// The time in seconds before a projectile is destroyed. On a bounded field projectiles usually leave the field before
+    pub projectile_lifetime: f32,
+    /// Whether enemies bounce off each other. If this is false enemies pass through each other
+    pub enemy_collisions: bool,
+    /// How much of their speed towards each other enemies keep when they bounce off each other. 1 is a perfectly elastic bounce, 0 stops them
+    pub enemy_restitution: f32,
+    /// How fast enemy ships can turn in radians per second if the ship is fully visible
+    pub enemy_turn_rate: f32,
+    /// The distance at which enemy ships circle around the ship
+    pub enemy_orbit_radius: f32,
+    /// The timeout between the shots of an enemy ship in seconds at the start of the game
+    pub enemy_shoot_timeout: f32,
+    /// The score at which enemy ships shoot twice as often as at the start of the game
+    pub enemy_shoot_timeout_score: f64,
+    /// The speed of the projectiles of enemy ships in pixels per second
+    pub enemy_projectile_speed: f32,
+    /// The score between the appearance of two gravity wells. 0 disables gravity wells
+    pub gravity_well_interval: f64,
+    /// The maximum number of gravity wells on the field
+    pub max_gravity_wells: u32,
+    /// The strength of the pull of new gravity wells. An object at distance r is accelerated by strength / r² pixels per second squared
+    pub gravity_well_strength: f32,
+    /// The radius of the event horizon of new gravity wells
+    pub gravity_well_horizon: f32,
+    /// The scores at which a boss appears, in ascending order
+    pub boss_scores: Vec<f64>,
+    /// The health of the first boss. Every following boss has this much more health
+    pub boss_health: i32,
+    /// The coins gained by defeating a boss. This is multiplied by the coin multiplier like the coins of enemies
+    pub boss_coins: i32,
+    /// The speed of bosses in pixels per second
+    pub boss_speed: f32,
+    /// The time in seconds between two waves, in which the player can shop
+    pub intermission_time: f32,
+    /// The kinds of enemies. Enemies refer to them by name
+    pub archetypes: Vec<Archetype>,
+    /// The chance that a destroyed enemy drops a power-up. Number between 0 and 1
+    pub power_up_probability: f32,
+    /// The time in seconds a dropped power-up stays on the field
+    pub power_up_lifetime: f32,
+    /// The time in seconds the effect of a collected power-up lasts
+    pub power_up_duration: f32,
+    /// The coins gained by collecting a coin burst. This is multiplied by the coin multiplier
+    pub power_up_coins: i32,
+    /// The number of projectiles the ship fires with every shot
+    pub volley_size: u32,
+    /// Whether the ship is protected by a shield. A shielded ship can't be hit
+    pub ship_shielded: bool,
+    /// The distance up to which enemies of a swarm see each other and flock together
+    pub swarm_perception: f32,
+    /// The distance enemies of a swarm try to keep between each other
+    pub swarm_separation: f32,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GameSettings {
@@ -53,20 +123,58 @@ impl GameSettings {
         let height = 700;
         let field: Field = Field::new(width, height);
         Self {
-            projectile_speed: 5.0,
-            asteroid_probability: 0.6,
-            enemy_speed_multiplier: 2.0,
+            projectile_speed: 250.0,
+            enemy_speed_multiplier: 100.0,
             coin_multiplier: 10,
-            score_increase: 0.03,
+            score_increase: 1.5,
             ship_visibility: 5.0,
-            enemy_min_speed: 0.5,
-            shoot_timeout: 70,
+            enemy_min_speed: 25.0,
+            shoot_timeout: 1.4,
             max_lives: 3,
             field,
             ship: Ship::new(&field),
-            invulnerability_time: 100,
-            enemy_spawn_timeout: 80.0,
+            ship_movement: None,
+            invulnerability_time: 2.0,
+            enemy_spawn_timeout: 1.6,
             projectile_hits: 1,
+            projectile_lifetime: 4.0,
+            enemy_collisions: true,
+            enemy_restitution: 0.9,
+            enemy_turn_rate: 2.0,
+            enemy_orbit_radius: 150.0,
+            enemy_shoot_timeout: 3.0,
+            enemy_shoot_timeout_score: 500.0,
+            enemy_projectile_speed: 200.0,
+            gravity_well_interval: 300.0,
+            max_gravity_wells: 3,
+            gravity_well_strength: 1_000_000.0,
+            gravity_well_horizon: 20.0,
+            boss_scores: vec![250.0, 750.0, 1500.0],
+            boss_health: 60,
+            boss_coins: 50,
+            boss_speed: 60.0,
+            intermission_time: 15.0,
+            archetypes: Archetype::catalog().to_vec(),
+            power_up_probability: 0.08,
+            power_up_lifetime: 8.0,
+            power_up_duration: 10.0,
+            power_up_coins: 20,
+            volley_size: 1,
+            ship_shielded: false,
+            swarm_perception: 90.0,
+            swarm_separation: 24.0,
         }
     }
+
+    /// Returns the archetype with the given name.
+    pub fn archetype(&self, name: &str) -> Option<&Archetype> {
+        self.archetypes
+            .iter()
+            .find(|archetype| archetype.name == name)
+    }
+
+    /// Returns the timeout between the shots of an enemy ship at the given score. Enemy ships shoot more often the higher the score.
+    pub fn enemy_shoot_timeout(&self, score: f64) -> f32 {
+        self.enemy_shoot_timeout / (1.0 + score / self.enemy_shoot_timeout_score.max(1.0)) as f32
+    }
 }
@@ -1,22 +1,73 @@
 //! The field module contains the field struct.
 
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::wasm_bindgen;
 
+/// What happens to objects that leave the field.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FieldTopology {
+    /// Objects that leave the field are removed
+    #[default]
+    Bounded,
+    /// Objects that leave the field re-enter it on the opposite edge, like in the classic Asteroids
+    Toroidal,
+}
+
 /// A field is the area in which the game takes place.
 ///
 /// It is defined by its width and height.
 #[wasm_bindgen]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Field {
     /// The width of the field
     pub width: i32,
     /// The height of the field
     pub height: i32,
+    /// What happens to objects that leave the field
+    #[serde(default)]
+    pub topology: FieldTopology,
 }
 
 impl Field {
-    /// Creates a new field with the given width and height
+    /// Creates a new bounded field with the given width and height
     pub fn new(width: i32, height: i32) -> Self {
-        Self { width, height }
+        Self {
+            width,
+            height,
+            topology: FieldTopology::Bounded,
+        }
+    }
+
+    /// Whether objects re-enter the field on the opposite edge.
+    pub fn wraps(&self) -> bool {
+        self.topology == FieldTopology::Toroidal
+    }
+
+    /// Moves a position that left a toroidal field back onto the field. Positions on a bounded field are not changed.
+    pub fn wrap(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        if !self.wraps() {
+            return (x, y);
+        }
+        (
+            x.rem_euclid(self.width as f32),
+            y.rem_euclid(self.height as f32),
+        )
+    }
+
+    /// Returns the position of `other` as seen from `position`.
+    ///
+    /// On a toroidal field every object can be reached across the edges as well. This returns the closest of these images of
+    /// `other`, which may lie outside of the field. On a bounded field `other` is returned unchanged.
+    pub fn nearest_image(&self, position: (f32, f32), other: (f32, f32)) -> (f32, f32) {
+        if !self.wraps() {
+            return other;
+        }
+        let (width, height) = (self.width as f32, self.height as f32);
+        let (dx, dy) = (other.0 - position.0, other.1 - position.1);
+        (
+            position.0 + dx - width * (dx / width).round(),
+            position.1 + dy - height * (dy / height).round(),
+        )
     }
 }
@@ -1,16 +1,50 @@
 //! The ship is the player's avatar in the game.
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::model::{
-    game_settings::field::Field,
-    positioned::{Moves, Positioned},
+    collider::Collider, game::TICK_DURATION, game_settings::field::Field, positioned::Positioned,
 };
 
+/// The outline of the ship asset facing in positive x direction, relative to the size of the ship.
+///
+/// The asset is an arrow, the notch at its back is left out because colliders have to be convex.
+const HULL: [(f32, f32); 3] = [(0.3464, -0.0198), (-0.3416, 0.3517), (-0.3517, -0.3416)];
+
+/// How the ship flies if it can move freely.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ShipMovement {
+    /// The acceleration in pixels per second squared while the ship is thrusting
+    pub thrust: f32,
+    /// The fraction of its velocity the ship loses per second
+    pub drag: f32,
+    /// The maximum speed of the ship in pixels per second
+    pub max_speed: f32,
+}
+
+impl Default for ShipMovement {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShipMovement {
+    /// Creates the default movement model
+    pub fn new() -> Self {
+        Self {
+            thrust: 375.0,
+            drag: 0.395,
+            max_speed: 300.0,
+        }
+    }
+}
+
 /// The ship is the player's avatar in the game.
 ///
-/// It stays in the middle of the screen and can only be rotated by the player.
+/// By default it stays in the middle of the screen and can only be rotated by the player.
+/// If [ship movement](crate::game_settings::GameSettings::ship_movement) is enabled it accelerates in the direction it is looking while it is thrusting.
 #[wasm_bindgen]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Ship {
     /// The x position of the ship
     pub x: f32,
@@ -24,6 +58,15 @@ pub struct Ship {
     pub height: f32,
     /// The width of the ship
     pub width: f32,
+    /// The x velocity of the ship in pixels per second
+    #[serde(default)]
+    pub vx: f32,
+    /// The y velocity of the ship in pixels per second
+    #[serde(default)]
+    pub vy: f32,
+    /// Whether the engine of the ship is running
+    #[serde(default)]
+    pub thrusting: bool,
 }
 
 #[wasm_bindgen]
@@ -37,6 +80,9 @@ impl Ship {
             dy: 0.0,
             width: 80.0,
             height: 80.0,
+            vx: 0.0,
+            vy: 0.0,
+            thrusting: false,
         }
     }
     /// Calculates the angle the ship is facing in radians
@@ -46,9 +92,55 @@ impl Ship {
     }
 }
 
+impl Ship {
+    /// Accelerates the ship while it is thrusting, slows it down by the drag and moves it one tick.
+    ///
+    /// The ship can't leave a bounded field and wraps around on a toroidal field.
+    pub fn move_tick(&mut self, movement: &ShipMovement, field: &Field) {
+        let length = (self.dx.powi(2) + self.dy.powi(2)).sqrt();
+        if self.thrusting && length > 0.0 {
+            // The direction of the ship points down the screen, the field points up
+            self.vx += self.dx / length * movement.thrust * TICK_DURATION;
+            self.vy -= self.dy / length * movement.thrust * TICK_DURATION;
+        }
+
+        let drag = (1.0 - movement.drag).powf(TICK_DURATION);
+        self.vx *= drag;
+        self.vy *= drag;
+        let speed = (self.vx.powi(2) + self.vy.powi(2)).sqrt();
+        if speed > movement.max_speed {
+            self.vx *= movement.max_speed / speed;
+            self.vy *= movement.max_speed / speed;
+        }
+
+        let (x, y) = field.wrap((
+            self.x + self.vx * TICK_DURATION,
+            self.y + self.vy * TICK_DURATION,
+        ));
+        let (width, height) = (field.width as f32, field.height as f32);
+        // The ship stops at the edges of a bounded field
+        if !(0.0..=width).contains(&x) {
+            self.vx = 0.0;
+        }
+        if !(0.0..=height).contains(&y) {
+            self.vy = 0.0;
+        }
+        self.x = x.clamp(0.0, width);
+        self.y = y.clamp(0.0, height);
+    }
+}
+
 impl Positioned for Ship {
-    fn is_round(&self) -> bool {
-        true
+    /// The outline of the asset, rotated in the direction the ship is looking.
+    ///
+    /// The direction of the ship is in screen coordinates where y points down, so the angle is mirrored.
+    fn collider(&self) -> Collider {
+        Collider::Polygon(
+            HULL.iter()
+                .map(|(x, y)| (x * self.width, y * self.height))
+                .collect(),
+        )
+        .rotated(-self.get_angle())
     }
 
     fn set_position(&mut self, x: f32, y: f32) {
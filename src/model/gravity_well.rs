@@ -0,0 +1,124 @@
+//! Gravity wells are hazards that appear on the field later in the game.
+//!
+//! They pull enemies and projectiles towards them and destroy everything that crosses their event horizon.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{
+    collider::Collider,
+    game::{GameRng, TICK_DURATION},
+    game_settings::{field::Field, GameSettings},
+    positioned::{Moves, Positioned},
+};
+
+/// The minimum distance between a new gravity well and the ship, so the ship isn't swallowed right away.
+const MIN_SHIP_DISTANCE: f32 = 250.0;
+
+/// The minimum distance between a new gravity well and the edges of the field.
+const EDGE_MARGIN: f32 = 100.0;
+
+/// The number of random positions that are tried for a new gravity well before giving up.
+const PLACEMENT_ATTEMPTS: u32 = 20;
+
+/// A gravity well is a black hole at a fixed position on the field.
+///
+/// Every tick it accelerates enemies and projectiles towards its center by the inverse square of their distance, which bends the shots of the player off course.
+/// Enemies and projectiles whose center crosses the event horizon are destroyed.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct GravityWell {
+    /// The id of the gravity well. This is used to identify the gravity well.
+    pub id: i64,
+    /// The x position of the gravity well
+    pub x: f32,
+    /// The y position of the gravity well
+    pub y: f32,
+    /// The strength of the pull. An object at distance r is accelerated by strength / r² pixels per second squared
+    pub strength: f32,
+    /// The radius of the event horizon
+    pub horizon: f32,
+}
+
+impl GravityWell {
+    /// Creates a new gravity well at a random position that is far enough away from the ship.
+    ///
+    /// Returns none if no such position was found within 20 tries, e.g. because the field is too small.
+    pub fn new(game_settings: &GameSettings, id: i64, rng: &mut GameRng) -> Option<Self> {
+        let field = &game_settings.field;
+        let ship = game_settings.ship.get_position();
+        let (width, height) = (field.width.max(0) as f32, field.height.max(0) as f32);
+        // On narrow fields the margin shrinks, so there is always a range to place the well in
+        let (margin_x, margin_y) = (EDGE_MARGIN.min(width / 2.0), EDGE_MARGIN.min(height / 2.0));
+
+        let (x, y) = (0..PLACEMENT_ATTEMPTS).find_map(|_| {
+            let position = (
+                rng.gen_range(margin_x..=width - margin_x),
+                rng.gen_range(margin_y..=height - margin_y),
+            );
+            let (ship_x, ship_y) = field.nearest_image(position, ship);
+            ((ship_x - position.0).powi(2) + (ship_y - position.1).powi(2)
+                >= MIN_SHIP_DISTANCE.powi(2))
+            .then_some(position)
+        })?;
+
+        Some(Self {
+            id,
+            x,
+            y,
+            strength: game_settings.gravity_well_strength,
+            horizon: game_settings.gravity_well_horizon,
+        })
+    }
+
+    /// Returns the offset from the object to the center of the well. On a toroidal field the well pulls across the edges.
+    fn offset(&self, object: &dyn Positioned, field: &Field) -> (f32, f32) {
+        let (x, y) = object.get_center();
+        let (well_x, well_y) = field.nearest_image((x, y), self.get_position());
+        (well_x - x, well_y - y)
+    }
+
+    /// Accelerates the object towards the well for one tick.
+    ///
+    /// The pull is capped at the strength it has at the event horizon, so objects passing close to the center aren't flung away.
+    pub fn pull(&self, object: &mut impl Moves, field: &Field) {
+        let (offset_x, offset_y) = self.offset(object, field);
+        let distance = (offset_x.powi(2) + offset_y.powi(2)).sqrt();
+        if distance == 0.0 {
+            return;
+        }
+
+        let acceleration = self.strength / distance.max(self.horizon).powi(2);
+        let (dx, dy) = object.direction();
+        object.set_direction(
+            dx + offset_x / distance * acceleration * TICK_DURATION,
+            dy + offset_y / distance * acceleration * TICK_DURATION,
+        );
+    }
+
+    /// Checks if the center of the object crossed the event horizon.
+    pub fn swallows(&self, object: &dyn Positioned, field: &Field) -> bool {
+        let (offset_x, offset_y) = self.offset(object, field);
+        offset_x.powi(2) + offset_y.powi(2) < self.horizon.powi(2)
+    }
+}
+
+impl Positioned for GravityWell {
+    fn collider(&self) -> Collider {
+        Collider::Circle(self.horizon)
+    }
+
+    fn get_position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn dimensions(&self) -> (f32, f32) {
+        (self.horizon * 2.0, self.horizon * 2.0)
+    }
+}
@@ -0,0 +1,23 @@
+//! This module contains the id generator used to hand out ids to game objects.
+
+use std::cell::Cell;
+
+use serde::{Deserialize, Serialize};
+
+/// Generates monotonically increasing ids for the objects of one game.
+///
+/// Every [Game](super::game::Game) owns its own generator, so two games with the same inputs hand out the same ids.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct IdGenerator {
+    /// The id that will be returned by the next call to [next_id](IdGenerator::next_id)
+    next: Cell<i64>,
+}
+
+impl IdGenerator {
+    /// Returns the next id.
+    pub fn next_id(&self) -> i64 {
+        let id = self.next.get();
+        self.next.set(id + 1);
+        id
+    }
+}
@@ -1,6 +1,9 @@
 //! This module contains traits for objects that have a position and dimensions.
 
-use super::game_settings::field::Field;
+use super::{collider::Collider, game::TICK_DURATION, game_settings::field::Field};
+
+/// The bounding box of an object as (min x, min y, max x, max y).
+pub type BoundingBox = (f32, f32, f32, f32);
 
 /// A trait for objects that have a position and dimensions.
 ///
@@ -12,8 +15,8 @@ pub trait Positioned {
     fn set_position(&mut self, x: f32, y: f32);
     /// The dimensions of the object returns (width, height).
     fn dimensions(&self) -> (f32, f32);
-    /// Whether the object is round or not. This is used to calculate collisions more accurately. If this is true, the width is treated as a radius.
-    fn is_round(&self) -> bool;
+    /// The shape of the object relative to its center. It has to fit into the [dimensions](Positioned::dimensions) of the object.
+    fn collider(&self) -> Collider;
 
     /// Returns the width of the object.
     fn get_width(&self) -> f32 {
@@ -43,24 +46,28 @@ pub trait Positioned {
         (x, y)
     }
 
-    /// Checks if the object collides with another object.
-    fn is_collision(&self, other: &dyn Positioned) -> bool {
+    /// Returns the bounding box of the object. It contains every point [is_collision](Positioned::is_collision) can hit.
+    fn bounding_box(&self) -> BoundingBox {
         let (x, y) = self.get_center();
-        let (x2, y2) = other.get_center();
         let (width, height) = self.dimensions();
-        let (width2, height2) = other.dimensions();
-        let x_distance = (x - x2).abs();
-        let y_distance = (y - y2).abs();
-
-        if self.is_round() && other.is_round() {
-            let radius = width / 2.0;
-            let radius2 = width2 / 2.0;
-
-            let distance = (x_distance * x_distance + y_distance * y_distance).sqrt();
-            return distance < (radius + radius2) * 0.8;
-        }
+        (
+            x - width / 2.0,
+            y - height / 2.0,
+            x + width / 2.0,
+            y + height / 2.0,
+        )
+    }
 
-        x_distance < (width + width2) / 3.0 && y_distance < (height + height2) / 3.0
+    /// Checks if the object collides with another object on the field.
+    ///
+    /// On a toroidal field objects also collide across the edges of the field.
+    fn is_collision(&self, other: &dyn Positioned, field: &Field) -> bool {
+        let position = self.get_center();
+        self.collider().intersects(
+            position,
+            &other.collider(),
+            field.nearest_image(position, other.get_center()),
+        )
     }
 }
 
@@ -68,15 +75,57 @@ pub trait Positioned {
 ///
 /// This trait is used to move objects in the game per frame.
 pub trait Moves: Positioned {
-    /// The direction the object moves in. Returns (dx, dy).
+    /// The direction the object moves in. Returns (dx, dy) in pixels per second.
     fn direction(&self) -> (f32, f32);
-    /// Moves the object one tick.
+    /// Sets the direction the object moves in.
+    fn set_direction(&mut self, dx: f32, dy: f32);
+    /// The distance the object travels in one tick. Returns (dx, dy).
+    fn travel(&self) -> (f32, f32) {
+        let (dx, dy) = self.direction();
+        (dx * TICK_DURATION, dy * TICK_DURATION)
+    }
+
+    /// Moves the object by the distance it travels in one tick. On a toroidal field objects that leave the field re-enter it on the opposite edge.
     ///
     /// Returns true if the object is still in the field.
     fn move_tick(&mut self, field: &Field) -> bool {
         let (x, y) = self.get_position();
-        let (dx, dy) = self.direction();
-        self.set_position(x + dx, y + dy);
-        !self.is_out_of_bounds(field)
+        let (dx, dy) = self.travel();
+        let (x, y) = field.wrap((x + dx, y + dy));
+        self.set_position(x, y);
+        field.wraps() || !self.is_out_of_bounds(field)
+    }
+
+    /// Returns the position of the object before its last move. On a toroidal field this is outside of the field if the object just wrapped around.
+    fn get_previous_position(&self) -> (f32, f32) {
+        let (x, y) = self.get_position();
+        let (dx, dy) = self.travel();
+        (x - dx, y - dy)
+    }
+
+    /// Returns the bounding box of the object over its whole last move.
+    fn swept_bounding_box(&self) -> BoundingBox {
+        let (min_x, min_y, max_x, max_y) = self.bounding_box();
+        let (dx, dy) = self.travel();
+        (
+            min_x.min(min_x - dx),
+            min_y.min(min_y - dy),
+            max_x.max(max_x - dx),
+            max_y.max(max_y - dy),
+        )
+    }
+
+    /// Checks if the object collided with another object at any point of its last move.
+    ///
+    /// Fast objects move further than their own size per tick, so checking only their current position would let them tunnel through small objects.
+    /// Instead the shape the object swept over during its last move is [tested](Collider::swept_intersects) against the other object.
+    fn is_swept_collision(&self, other: &dyn Positioned, field: &Field) -> bool {
+        let end = self.get_center();
+        self.collider().swept_intersects(
+            self.get_previous_position(),
+            end,
+            &other.collider(),
+            field.nearest_image(end, other.get_center()),
+        )
     }
 }
@@ -0,0 +1,211 @@
+//! Power-ups are pickups that destroyed enemies drop with the [power-up probability](GameSettings::power_up_probability).
+//!
+//! A power-up drifts through the field until it is collected, either by flying the ship into it or by shooting it.
+//! Some power-ups take effect once, the others last for a while. Those [effects](Effect) either change the [game settings](GameSettings) and remember what they changed, so they can be reverted cleanly even if items were bought in the meantime, or like rapid fire only scale a setting where it is used.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{
+    collider::Collider,
+    game::TICK_DURATION,
+    game_settings::GameSettings,
+    positioned::{Moves, Positioned},
+};
+
+/// The radius of a power-up
+const POWER_UP_RADIUS: f32 = 12.0;
+
+/// How much of the speed of the destroyed enemy a power-up keeps
+const DRIFT_FACTOR: f32 = 0.3;
+
+/// The share of the shoot timeout that is left while rapid fire is active
+const RAPID_FIRE_FACTOR: f32 = 0.5;
+
+/// The number of projectiles triple shot adds to every shot
+const TRIPLE_SHOT_EXTRA: u32 = 2;
+
+/// The kinds of power-ups.
+#[derive(TS, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[ts(export)]
+pub enum PowerUpKind {
+    /// The ship shoots twice as fast for a while
+    RapidFire,
+    /// Every shot fires a fan of three projectiles for a while
+    TripleShot,
+    /// The ship can't be hit for a while
+    Shield,
+    /// The ship gains a life, up to the maximum number of lives
+    ExtraLife,
+    /// The player gains the [power-up coins](GameSettings::power_up_coins) right away
+    CoinBurst,
+}
+
+impl PowerUpKind {
+    /// All kinds of power-ups. A dropped power-up is equally likely to be any of them.
+    pub const ALL: [PowerUpKind; 5] = [
+        PowerUpKind::RapidFire,
+        PowerUpKind::TripleShot,
+        PowerUpKind::Shield,
+        PowerUpKind::ExtraLife,
+        PowerUpKind::CoinBurst,
+    ];
+
+    /// Returns true if the power-up changes the game settings for the [power-up duration](GameSettings::power_up_duration).
+    pub fn is_timed(&self) -> bool {
+        matches!(
+            self,
+            PowerUpKind::RapidFire | PowerUpKind::TripleShot | PowerUpKind::Shield
+        )
+    }
+}
+
+/// A power-up drifting through the field.
+#[derive(TS, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[ts(export)]
+pub struct PowerUp {
+    /// The id of the power-up. This is used to identify the power-up.
+    pub id: i64,
+    /// The x position of the power-up
+    pub x: f32,
+    /// The y position of the power-up
+    pub y: f32,
+    /// The x velocity of the power-up in pixels per second
+    pub dx: f32,
+    /// The y velocity of the power-up in pixels per second
+    pub dy: f32,
+    /// The radius of the power-up
+    pub radius: f32,
+    /// What the power-up does once it is collected
+    pub kind: PowerUpKind,
+    /// The time in seconds until the power-up disappears
+    pub lifetime: f32,
+}
+
+impl PowerUp {
+    /// Creates a power-up of a random kind where an enemy was destroyed. It drifts on slowly in the direction the enemy flew.
+    pub fn drop_at(
+        (x, y): (f32, f32),
+        (dx, dy): (f32, f32),
+        game_settings: &GameSettings,
+        id: i64,
+        rng: &mut impl Rng,
+    ) -> Self {
+        Self {
+            id,
+            x,
+            y,
+            dx: dx * DRIFT_FACTOR,
+            dy: dy * DRIFT_FACTOR,
+            radius: POWER_UP_RADIUS,
+            kind: PowerUpKind::ALL[rng.gen_range(0..PowerUpKind::ALL.len())],
+            lifetime: game_settings.power_up_lifetime,
+        }
+    }
+}
+
+impl Positioned for PowerUp {
+    fn collider(&self) -> Collider {
+        Collider::Circle(self.radius)
+    }
+
+    fn get_position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn dimensions(&self) -> (f32, f32) {
+        (self.radius * 2.0, self.radius * 2.0)
+    }
+}
+
+impl Moves for PowerUp {
+    fn direction(&self) -> (f32, f32) {
+        (self.dx, self.dy)
+    }
+
+    fn set_direction(&mut self, dx: f32, dy: f32) {
+        self.dx = dx;
+        self.dy = dy;
+    }
+}
+
+/// A timed power-up that currently changes the game settings.
+#[derive(TS, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[ts(export)]
+pub struct Effect {
+    /// The kind of the power-up that caused the effect
+    pub kind: PowerUpKind,
+    /// The time in seconds until the effect expires
+    pub time_left: f32,
+    /// The amount the effect changed its setting by. Reverting the effect takes away exactly this amount
+    pub amount: f32,
+}
+
+impl Effect {
+    /// Applies the effect of a timed power-up to the game settings and returns it.
+    ///
+    /// The changes are added on top of the current settings, so items bought while the effect is active stay when it is [reverted](Effect::revert).
+    pub fn apply(kind: PowerUpKind, game_settings: &mut GameSettings) -> Self {
+        let amount = match kind {
+            PowerUpKind::TripleShot => {
+                game_settings.volley_size += TRIPLE_SHOT_EXTRA;
+                TRIPLE_SHOT_EXTRA as f32
+            }
+            PowerUpKind::Shield => {
+                game_settings.ship_shielded = true;
+                1.0
+            }
+            PowerUpKind::RapidFire | PowerUpKind::ExtraLife | PowerUpKind::CoinBurst => 0.0,
+        };
+        Self {
+            kind,
+            time_left: game_settings.power_up_duration,
+            amount,
+        }
+    }
+
+    /// Takes the changes of the effect back.
+    pub fn revert(&self, game_settings: &mut GameSettings) {
+        match self.kind {
+            PowerUpKind::TripleShot => game_settings.volley_size -= self.amount as u32,
+            PowerUpKind::Shield => game_settings.ship_shielded = false,
+            PowerUpKind::RapidFire | PowerUpKind::ExtraLife | PowerUpKind::CoinBurst => (),
+        }
+    }
+
+    /// Applies the changes of the effect again. This is used to rebuild the settings of a saved game.
+    pub fn reapply(&self, game_settings: &mut GameSettings) {
+        match self.kind {
+            PowerUpKind::TripleShot => game_settings.volley_size += self.amount as u32,
+            PowerUpKind::Shield => game_settings.ship_shielded = true,
+            PowerUpKind::RapidFire | PowerUpKind::ExtraLife | PowerUpKind::CoinBurst => (),
+        }
+    }
+
+    /// Returns the time between two shots of the ship while the effects are active.
+    ///
+    /// Rapid fire doesn't change the [shoot timeout](GameSettings::shoot_timeout) itself but scales it whenever the ship shoots, so bought items
+    /// can never push it below zero.
+    pub fn shoot_timeout(effects: &[Effect], game_settings: &GameSettings) -> f32 {
+        if effects
+            .iter()
+            .any(|effect| effect.kind == PowerUpKind::RapidFire)
+        {
+            game_settings.shoot_timeout * RAPID_FIRE_FACTOR
+        } else {
+            game_settings.shoot_timeout
+        }
+    }
+
+    /// Lets the effect run for one tick. Returns true if it is still active.
+    pub fn tick(&mut self) -> bool {
+        self.time_left -= TICK_DURATION;
+        self.time_left > 0.0
+    }
+}
@@ -4,22 +4,39 @@ use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use super::{
+    collider::Collider,
+    enemy::Enemy,
     game::Game,
+    game_settings::GameSettings,
     positioned::{Moves, Positioned},
 };
 
-/// A projectile is a bullet that is shot by the player.
+/// The angle in radians between two projectiles of a volley.
+const VOLLEY_SPREAD: f32 = 0.15;
+
+/// Who shot a projectile. This decides what the projectile can hit.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum Faction {
+    /// Shot by the player. Hits enemies
+    #[default]
+    Player,
+    /// Shot by an enemy ship. Hits the ship
+    Hostile,
+}
+
+/// A projectile is a bullet that is shot by the player or by an enemy ship.
 ///
-/// It moves in a straight line and can hit enemies.
+/// It moves in a straight line unless it is pulled by a gravity well and can hit enemies or the ship, depending on its [faction](Faction).
 /// The projectile is destroyed when the hit count reaches 0 or it leaves the field.
 #[wasm_bindgen]
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Projectile {
     /// The id of the projectile. This is used to identify the projectile.
     pub id: i64,
-    /// The x direction of the projectile
+    /// The x velocity of the projectile in pixels per second
     pub dx: f32,
-    /// The y direction of the projectile
+    /// The y velocity of the projectile in pixels per second
     pub dy: f32,
     /// The x position of the projectile
     pub x: f32,
@@ -31,30 +48,100 @@ pub struct Projectile {
     pub damage: i32,
     /// The height of the projectile
     pub radius: f32,
+    /// The time in seconds until the projectile is destroyed
+    #[serde(default = "default_lifetime")]
+    pub lifetime: f32,
+    /// Who shot the projectile. Projectiles saved before enemies could shoot were shot by the player
+    #[serde(default)]
+    pub faction: Faction,
+}
+
+/// Projectiles saved before they had a lifetime live as long as new projectiles.
+fn default_lifetime() -> f32 {
+    GameSettings::new().projectile_lifetime
 }
 
 impl Projectile {
     pub fn new(game: &Game) -> Self {
+        Self::aimed(game, 0.0)
+    }
+
+    /// Creates the projectiles of one shot of the ship. If the [volley size](GameSettings::volley_size) is larger than 1 they fan out around the direction of the ship.
+    pub fn volley(game: &Game) -> Vec<Self> {
+        let size = game.game_settings.volley_size.max(1);
+        (0..size)
+            .map(|i| Self::aimed(game, (i as f32 - (size - 1) as f32 / 2.0) * VOLLEY_SPREAD))
+            .collect()
+    }
+
+    /// Creates a projectile of the ship that flies at the given angle in radians to the direction of the ship.
+    fn aimed(game: &Game, angle: f32) -> Self {
         let (dx, dy) = (game.game_settings.ship.dx, game.game_settings.ship.dy);
         let (x, y) = game.game_settings.ship.get_center();
         let length = (dx.powi(2) + dy.powi(2)).sqrt();
-        let (norm_dx, norm_dy) = (dx / length, dy / length);
+        let (sin, cos) = angle.sin_cos();
+        let (norm_dx, norm_dy) = (
+            (dx * cos - dy * sin) / length,
+            (dx * sin + dy * cos) / length,
+        );
         Self {
             id: game.get_next_id(),
-            dx: norm_dx * game.game_settings.projectile_speed as f32,
-            dy: -norm_dy * game.game_settings.projectile_speed as f32,
+            // Projectiles keep the velocity of the ship they are shot from
+            dx: norm_dx * game.game_settings.projectile_speed + game.game_settings.ship.vx,
+            dy: -norm_dy * game.game_settings.projectile_speed + game.game_settings.ship.vy,
             x: x + (norm_dx * game.game_settings.ship.width / 2.0),
             y: y - (norm_dy * game.game_settings.ship.height / 2.0),
             hits: game.game_settings.projectile_hits,
             damage: 1,
             radius: 20.0,
+            lifetime: game.game_settings.projectile_lifetime,
+            faction: Faction::Player,
+        }
+    }
+
+    /// Creates a projectile shot by an enemy ship at the ship.
+    pub fn hostile(enemy: &Enemy, game_settings: &GameSettings, id: i64) -> Self {
+        let (x, y) = enemy.get_center();
+        let (ship_x, ship_y) = game_settings
+            .field
+            .nearest_image((x, y), game_settings.ship.get_center());
+        Self::hostile_towards(
+            (x, y),
+            (ship_x - x, ship_y - y),
+            enemy.radius,
+            game_settings,
+            id,
+        )
+    }
+
+    /// Creates a hostile projectile that flies in the given direction. It starts at the given distance from the position, so it doesn't start inside the object that shoots it.
+    pub fn hostile_towards(
+        (x, y): (f32, f32),
+        (dx, dy): (f32, f32),
+        distance: f32,
+        game_settings: &GameSettings,
+        id: i64,
+    ) -> Self {
+        let length = (dx.powi(2) + dy.powi(2)).sqrt().max(f32::EPSILON);
+        let (norm_dx, norm_dy) = (dx / length, dy / length);
+        Self {
+            id,
+            dx: norm_dx * game_settings.enemy_projectile_speed,
+            dy: norm_dy * game_settings.enemy_projectile_speed,
+            x: x + norm_dx * distance,
+            y: y + norm_dy * distance,
+            hits: 1,
+            damage: 1,
+            radius: 12.0,
+            lifetime: game_settings.projectile_lifetime,
+            faction: Faction::Hostile,
         }
     }
 }
 
 impl Positioned for Projectile {
-    fn is_round(&self) -> bool {
-        true
+    fn collider(&self) -> Collider {
+        Collider::Circle(self.radius / 2.0)
     }
 
     fn get_position(&self) -> (f32, f32) {
@@ -75,4 +162,9 @@ impl Moves for Projectile {
     fn direction(&self) -> (f32, f32) {
         (self.dx, self.dy)
     }
+
+    fn set_direction(&mut self, dx: f32, dy: f32) {
+        self.dx = dx;
+        self.dy = dy;
+    }
 }
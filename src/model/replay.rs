@@ -0,0 +1,166 @@
+//! This module contains the replay system. A replay is the seed of a game together with every action taken in it.
+//!
+//! As the game is deterministic for a given seed, re-driving [Game::step] with the recorded actions reproduces the run exactly.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error;
+
+use super::{
+    error_message::ErrorMessage,
+    game::{Game, GameAction},
+    game_settings::{field::FieldTopology, ship::ShipMovement},
+};
+
+/// The version of the replay format written by this version of the game.
+///
+/// A replay only plays out the same way with the model it was recorded with. Bump this whenever a change to the simulation changes how recorded inputs play out.
+pub const REPLAY_VERSION: u32 = 1;
+
+/// The errors that can occur while playing a replay.
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    /// The replay was recorded with the given version of the replay format, which this version of the game can't play
+    UnsupportedVersion(u32),
+}
+
+impl From<ReplayError> for ErrorMessage {
+    fn from(replay_error: ReplayError) -> Self {
+        match replay_error {
+            ReplayError::UnsupportedVersion(_) => {
+                error!("The replay was recorded with another version of the game")
+            }
+        }
+    }
+}
+
+/// A recorded run of the game.
+///
+/// Ticks are not stored one by one. Instead every other action is stored together with the number of ticks that happened before it, which keeps replays small.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// The version of the replay format. See [REPLAY_VERSION]. Replays recorded before replays were versioned have version 0.
+    #[serde(default)]
+    pub version: u32,
+    /// The seed the recorded game was started with
+    pub seed: u64,
+    /// The topology of the field the recorded game was played on
+    #[serde(default)]
+    pub topology: FieldTopology,
+    /// Whether the ship could move freely in the recorded game
+    #[serde(default)]
+    pub ship_movement: bool,
+    /// The number of ticks that were recorded
+    pub ticks: u32,
+    /// The recorded actions (except ticks) together with their tick index
+    pub inputs: Vec<(u32, GameAction)>,
+}
+
+impl Replay {
+    /// Creates a new empty replay for a game with the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            topology: FieldTopology::Bounded,
+            ship_movement: false,
+            ticks: 0,
+            inputs: vec![],
+        }
+    }
+
+    /// Records an action.
+    pub fn record(&mut self, action: GameAction) {
+        match action {
+            GameAction::Tick => self.ticks += 1,
+            action => self.inputs.push((self.ticks, action)),
+        }
+    }
+
+    /// Checks that the replay was recorded with the replay format of this version of the game.
+    pub fn check_version(&self) -> Result<(), ReplayError> {
+        if self.version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(self.version));
+        }
+        Ok(())
+    }
+
+    /// Creates the game the replay starts with.
+    pub fn new_game(&self) -> Game {
+        let mut game = Game::new_with_seed(self.seed);
+        game.game_settings.field.topology = self.topology;
+        if self.ship_movement {
+            game.game_settings.ship_movement = Some(ShipMovement::new());
+        }
+        game
+    }
+
+    /// Plays the whole replay and returns the game in the state the recording ended with.
+    ///
+    /// Fails if the replay was recorded with another [version](REPLAY_VERSION) of the replay format.
+    pub fn play(&self) -> Result<Game, ReplayError> {
+        self.check_version()?;
+        let mut game = self.new_game();
+        let mut player = ReplayPlayer::new(self.clone());
+        while player.step(&mut game) {}
+        Ok(game)
+    }
+
+    /// Serializes the replay to json.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Replays are always serializable")
+    }
+
+    /// Parses a replay from json.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Plays a replay back tick by tick. This is used to watch a recorded run.
+pub struct ReplayPlayer {
+    /// The replay that is played
+    replay: Replay,
+    /// The number of ticks that were already played
+    tick: u32,
+    /// The index of the next input to apply
+    next_input: usize,
+}
+
+impl ReplayPlayer {
+    /// Creates a new player that starts at the beginning of the replay.
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            tick: 0,
+            next_input: 0,
+        }
+    }
+
+    /// Applies the recorded actions of the current tick to the game and calculates the next frame.
+    ///
+    /// Returns false once the replay is over.
+    pub fn step(&mut self, game: &mut Game) -> bool {
+        while let Some((tick, action)) = self.replay.inputs.get(self.next_input) {
+            if *tick > self.tick {
+                break;
+            }
+            // Failed actions (e.g. buying an item without enough coins) failed during the recording as well
+            let _ = game.step(*action);
+            self.next_input += 1;
+        }
+
+        if self.tick >= self.replay.ticks {
+            return false;
+        }
+
+        let _ = game.step(GameAction::Tick);
+        self.tick += 1;
+        true
+    }
+
+    /// Returns the replay that is played.
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+}
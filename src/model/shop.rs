@@ -75,6 +75,12 @@ pub struct Shop {
     pub items: Vec<Item>,
 }
 
+impl Default for Shop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Shop {
     /// Create a new shop
     pub fn new() -> Self {
@@ -90,7 +96,7 @@ impl Shop {
                     max_level: 6,
                     level: 0,
                     apply_item: |settings, _| {
-                        settings.shoot_timeout -= 10;
+                        settings.shoot_timeout -= 0.2;
                     },
                 },
                 Item {
@@ -102,13 +108,13 @@ impl Shop {
                     max_level: 5,
                     level: 0,
                     apply_item: |settings, _| {
-                        settings.projectile_speed += 2.5;
+                        settings.projectile_speed += 125.0;
                     },
                 },
                 Item {
                     id: 2,
                     title: "Optic Obfuscator",
-                    description: "Decreases the chance of alien vessels to spot you",
+                    description: "Makes it harder for alien vessels to spot and follow you",
                     internal_price: 200,
                     price,
                     max_level: 4,
@@ -150,7 +156,7 @@ impl Shop {
                     max_level: 6,
                     level: 0,
                     apply_item: |settings, _| {
-                        settings.invulnerability_time += 50;
+                        settings.invulnerability_time += 1.0;
                     },
                 },
             ],
@@ -0,0 +1,174 @@
+//! This module contains the snapshot of a game. A snapshot contains the complete state of a [Game](super::game::Game).
+//!
+//! Restoring a snapshot puts the player back in exactly the same situation, including all enemies, projectiles and timers.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    archetype::Archetype,
+    boss::Boss,
+    enemy::Enemy,
+    game::{GameRng, GameState},
+    game_ressources::{validate_item_level, validate_resources, LoadError},
+    game_settings::GameSettings,
+    gravity_well::GravityWell,
+    id_generator::IdGenerator,
+    power_up::{Effect, PowerUp},
+    projectile::Projectile,
+    shop::Shop,
+    wave::Wave,
+};
+
+/// The maximum difference between a stored setting and the rebuilt setting. Items and effects change the settings in a different order during the game than while rebuilding them, so floats are not compared exactly.
+const SETTINGS_TOLERANCE: f32 = 1e-3;
+
+/// The complete state of a game.
+///
+/// Snapshots are created with [Game::snapshot](super::game::Game::snapshot) and restored with [Game::from_snapshot](super::game::Game::from_snapshot).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    /// The id used to save the game
+    pub id: Option<u32>,
+    /// The seed the game was created with
+    pub seed: u64,
+    /// The state of the random number generator
+    pub rng: GameRng,
+    /// The state of the id generator
+    pub id_generator: IdGenerator,
+    /// The game settings including the effects of all bought items
+    pub game_settings: GameSettings,
+    /// The levels of the shop items as (item id, level)
+    pub item_levels: Vec<(u32, u8)>,
+    /// The score of the game
+    pub score: f64,
+    /// The coins of the game
+    pub coins: i32,
+    /// The lives the player has left
+    pub lives: i32,
+    /// The time in seconds until the next shot can be fired
+    pub time_until_next_shot: f32,
+    /// The time in seconds until the ship is vulnerable again
+    pub time_until_vulnerable: f32,
+    /// The time in seconds until the next enemy is spawned
+    pub time_until_enemy_spawn: f32,
+    /// The state of the game
+    pub state: GameState,
+    /// The enemies in the game
+    pub enemies: Vec<Enemy>,
+    /// The gravity wells on the field
+    #[serde(default)]
+    pub gravity_wells: Vec<GravityWell>,
+    /// The boss on the field
+    #[serde(default)]
+    pub boss: Option<Boss>,
+    /// The index of the next boss score to reach
+    #[serde(default)]
+    pub next_boss: usize,
+    /// The current wave
+    #[serde(default)]
+    pub wave: Wave,
+    /// The time in seconds until the next wave starts
+    #[serde(default)]
+    pub time_until_next_wave: f32,
+    /// The power-ups on the field
+    #[serde(default)]
+    pub power_ups: Vec<PowerUp>,
+    /// The active effects of power-ups
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+    /// The projectiles in the game
+    pub projectiles: Vec<Projectile>,
+}
+
+impl GameSnapshot {
+    /// Serializes the snapshot to json.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Snapshots are always serializable")
+    }
+
+    /// Parses a snapshot from json.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Checks that the snapshot describes a game that can actually be reached.
+    ///
+    /// The stored settings are checked against the settings its items and active effects lead to, so they can't be changed on their own.
+    pub fn validate(&self) -> Result<(), LoadError> {
+        let shop = Shop::new();
+        for (index, (id, level)) in self.item_levels.iter().enumerate() {
+            validate_item_level(&shop, *id, *level as u32)?;
+            if self.item_levels[..index]
+                .iter()
+                .any(|(other, _)| other == id)
+            {
+                return Err(LoadError::LevelTooHigh(*id));
+            }
+        }
+        Archetype::validate_catalog(&self.game_settings.archetypes)
+            .map_err(LoadError::InvalidArchetypes)?;
+        self.validate_effects()?;
+        let settings = self.rebuild_settings(&shop);
+        validate_resources(self.coins, self.lives, settings.max_lives)?;
+        self.validate_settings(&settings)?;
+        if let Some(enemy) = self
+            .enemies
+            .iter()
+            .find(|enemy| enemy.archetype(&self.game_settings).is_none())
+        {
+            return Err(LoadError::UnknownArchetype(enemy.enemy_type.clone()));
+        }
+        Ok(())
+    }
+
+    /// Checks that only timed power-ups have effects, each at most once and by no more than collecting it in a new game changes the settings.
+    fn validate_effects(&self) -> Result<(), LoadError> {
+        for (index, effect) in self.effects.iter().enumerate() {
+            let max_amount = Effect::apply(effect.kind, &mut GameSettings::new()).amount;
+            if !effect.kind.is_timed()
+                || !(0.0..=max_amount).contains(&effect.amount)
+                || self.effects[..index]
+                    .iter()
+                    .any(|other| other.kind == effect.kind)
+            {
+                return Err(LoadError::InvalidSettings);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the settings of a new game with the items and active effects of the snapshot.
+    fn rebuild_settings(&self, shop: &Shop) -> GameSettings {
+        let mut settings = GameSettings::new();
+        for (id, level) in &self.item_levels {
+            if let Some(item) = shop.items.iter().find(|item| item.id == *id) {
+                for level in 1..=*level {
+                    (item.apply_item)(&mut settings, level);
+                }
+            }
+        }
+        for effect in &self.effects {
+            effect.reapply(&mut settings);
+        }
+        settings
+    }
+
+    /// Checks that the stored settings which items and effects change match the rebuilt settings.
+    fn validate_settings(&self, rebuilt: &GameSettings) -> Result<(), LoadError> {
+        let stored = &self.game_settings;
+        let close = |a: f32, b: f32| (a - b).abs() <= SETTINGS_TOLERANCE;
+        let valid = stored.max_lives == rebuilt.max_lives
+            && stored.projectile_hits == rebuilt.projectile_hits
+            && stored.coin_multiplier == rebuilt.coin_multiplier
+            && stored.volley_size == rebuilt.volley_size
+            && stored.ship_shielded == rebuilt.ship_shielded
+            && close(stored.shoot_timeout, rebuilt.shoot_timeout)
+            && close(stored.projectile_speed, rebuilt.projectile_speed)
+            && close(stored.ship_visibility, rebuilt.ship_visibility)
+            && close(stored.invulnerability_time, rebuilt.invulnerability_time);
+        if !valid {
+            return Err(LoadError::InvalidSettings);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+//! This module contains the broadphase of the collision detection.
+//!
+//! Objects are inserted into a uniform grid over the [field](Field). Only objects that share a cell can collide, so a collision
+//! check only has to look at the objects in the cells an object overlaps instead of at every object in the game.
+
+use std::ops::RangeInclusive;
+
+use super::{
+    game_settings::field::Field,
+    positioned::{BoundingBox, Moves, Positioned},
+};
+
+/// The default size of a cell in pixels. This is a bit larger than the biggest enemy.
+pub const DEFAULT_CELL_SIZE: f32 = 100.0;
+
+/// A uniform grid over the field that stores the indices of the objects overlapping each cell.
+///
+/// On a bounded field objects outside of the field are stored in the cells at the border of the field.
+/// On a toroidal field the grid wraps around like the field, so objects at the edge share cells with objects at the opposite edge.
+/// The indices of all cells are stored in one vec, sorted by cell, so building the grid only needs a few allocations.
+pub struct SpatialHash {
+    /// The width of a cell
+    cell_width: f32,
+    /// The height of a cell
+    cell_height: f32,
+    /// The number of cells in x direction
+    columns: usize,
+    /// The number of cells in y direction
+    rows: usize,
+    /// Whether the grid wraps around at its edges
+    wraps: bool,
+    /// The indices of the objects in cell `i` are `entries[cell_starts[i]..cell_starts[i + 1]]`
+    cell_starts: Vec<usize>,
+    /// The indices of the objects, sorted by cell
+    entries: Vec<usize>,
+}
+
+impl SpatialHash {
+    /// Creates a grid over the field containing the objects. The objects are identified by their index in the slice.
+    pub fn from_objects<T: Positioned>(field: &Field, objects: &[T]) -> Self {
+        Self::with_cell_size(
+            field,
+            DEFAULT_CELL_SIZE,
+            objects.iter().map(T::bounding_box),
+        )
+    }
+
+    /// Creates a grid over the field containing the objects over their whole last move. This is used for [swept collisions](Moves::is_swept_collision).
+    pub fn from_moving_objects<T: Moves>(field: &Field, objects: &[T]) -> Self {
+        Self::with_cell_size(
+            field,
+            DEFAULT_CELL_SIZE,
+            objects.iter().map(T::swept_bounding_box),
+        )
+    }
+
+    /// Creates a grid with the given cell size over the field containing the bounding boxes.
+    ///
+    /// On a toroidal field the cells are stretched a bit, so that the field is made up of whole cells.
+    pub fn with_cell_size(
+        field: &Field,
+        cell_size: f32,
+        bounding_boxes: impl Iterator<Item = BoundingBox>,
+    ) -> Self {
+        let (width, height) = (field.width as f32, field.height as f32);
+        let cells = |length: f32| {
+            let cells = if field.wraps() {
+                (length / cell_size).floor()
+            } else {
+                (length / cell_size).ceil()
+            };
+            (cells as usize).max(1)
+        };
+        let (columns, rows) = (cells(width), cells(height));
+        let mut grid = Self {
+            cell_width: if field.wraps() {
+                width / columns as f32
+            } else {
+                cell_size
+            },
+            cell_height: if field.wraps() {
+                height / rows as f32
+            } else {
+                cell_size
+            },
+            columns,
+            rows,
+            wraps: field.wraps(),
+            cell_starts: vec![0; columns * rows + 1],
+            entries: vec![],
+        };
+
+        // Count the objects per cell, then place every object at the end of its cells
+        let ranges: Vec<_> = bounding_boxes.map(|b| grid.cell_range(b)).collect();
+        for (columns, rows) in &ranges {
+            for row in rows.clone() {
+                for column in columns.clone() {
+                    let cell = grid.cell(column, row);
+                    grid.cell_starts[cell + 1] += 1;
+                }
+            }
+        }
+        for i in 1..grid.cell_starts.len() {
+            grid.cell_starts[i] += grid.cell_starts[i - 1];
+        }
+        let mut ends = grid.cell_starts.clone();
+        grid.entries = vec![0; *grid.cell_starts.last().unwrap_or(&0)];
+        for (index, (columns, rows)) in ranges.into_iter().enumerate() {
+            for row in rows {
+                for column in columns.clone() {
+                    let cell = grid.cell(column, row);
+                    grid.entries[ends[cell]] = index;
+                    ends[cell] += 1;
+                }
+            }
+        }
+        grid
+    }
+
+    /// Returns the indices of all objects that share a cell with the object, sorted in ascending order.
+    ///
+    /// Only these objects can collide with the object.
+    pub fn query(&self, object: &dyn Positioned) -> Vec<usize> {
+        let mut candidates = vec![];
+        self.query_into(object, &mut candidates);
+        candidates
+    }
+
+    /// Same as [query](SpatialHash::query), but writes the indices into the given vec to reuse its allocation.
+    pub fn query_into(&self, object: &dyn Positioned, candidates: &mut Vec<usize>) {
+        candidates.clear();
+        let (columns, rows) = self.cell_range(object.bounding_box());
+        for row in rows {
+            for column in columns.clone() {
+                let cell = self.cell(column, row);
+                candidates.extend_from_slice(
+                    &self.entries[self.cell_starts[cell]..self.cell_starts[cell + 1]],
+                );
+            }
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+    }
+
+    /// Returns the columns and rows of the cells the bounding box overlaps.
+    ///
+    /// On a bounded field they are clamped to the grid. On a toroidal field they may lie outside of the grid and are wrapped
+    /// by [cell](SpatialHash::cell), but never contain a cell twice.
+    fn cell_range(
+        &self,
+        (min_x, min_y, max_x, max_y): BoundingBox,
+    ) -> (RangeInclusive<i64>, RangeInclusive<i64>) {
+        let range = |min: f32, max: f32, cell_size: f32, cells: usize| {
+            let (first, last) = (
+                (min / cell_size).floor() as i64,
+                (max / cell_size).floor() as i64,
+            );
+            let cells = cells as i64;
+            if self.wraps {
+                first..=last.min(first + cells - 1)
+            } else {
+                first.clamp(0, cells - 1)..=last.clamp(0, cells - 1)
+            }
+        };
+        (
+            range(min_x, max_x, self.cell_width, self.columns),
+            range(min_y, max_y, self.cell_height, self.rows),
+        )
+    }
+
+    /// Returns the index of the cell in the column and row, wrapped around the grid.
+    fn cell(&self, column: i64, row: i64) -> usize {
+        let column = column.rem_euclid(self.columns as i64) as usize;
+        let row = row.rem_euclid(self.rows as i64) as usize;
+        row * self.columns + column
+    }
+}
@@ -0,0 +1,251 @@
+//! This module contains the steering behaviours of enemy ships.
+//!
+//! Most enemies, like asteroids and debris, drift in a straight line. Enemies whose [archetype](super::archetype::Archetype) hunts the ship, like enemy ships, instead choose a behaviour every tick and turn towards the direction it asks for.
+//! How fast they can turn is limited by the [turn rate](GameSettings::enemy_turn_rate) and scaled with the [visibility](GameSettings::ship_visibility) of the ship,
+//! so a ship that is hard to see is hard to follow.
+//!
+//! Enemies whose archetype [swarms](super::archetype::Archetype::swarms) don't steer on their own. They [flock](flock) like boids: every enemy of a swarm keeps its distance to its neighbours,
+//! flies in the same direction and towards the center of them, and is drawn towards the ship.
+
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{
+    enemy::Enemy, game::TICK_DURATION, game_settings::GameSettings, positioned::Positioned,
+};
+
+/// The longest time in seconds an enemy looks ahead when it pursues the ship.
+const MAX_LEAD_TIME: f32 = 2.0;
+
+/// How strongly enemies of a swarm push away from neighbours that come closer than the [separation](GameSettings::swarm_separation)
+const SEPARATION_WEIGHT: f32 = 1.5;
+
+/// How strongly enemies of a swarm match the direction of their neighbours
+const ALIGNMENT_WEIGHT: f32 = 1.0;
+
+/// How strongly enemies of a swarm fly towards the center of their neighbours
+const COHESION_WEIGHT: f32 = 0.8;
+
+/// How strongly enemies of a swarm are drawn towards a fully visible ship
+const ATTRACTION_WEIGHT: f32 = 0.7;
+
+/// How fast enemies of a swarm change their direction, in multiples of the difference to the direction they want to fly in per second
+const SWARM_AGILITY: f32 = 3.0;
+
+/// What an enemy is currently doing.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum Behaviour {
+    /// The enemy flies in a straight line. Enemies that don't [hunt](crate::archetype::Archetype::hunts) always drift
+    #[default]
+    Drift,
+    /// The enemy flies towards the ship
+    Seek,
+    /// The enemy flies towards the point where the moving ship will be when it gets there
+    Pursue,
+    /// The enemy circles around the ship at the [orbit radius](GameSettings::enemy_orbit_radius)
+    Orbit,
+    /// The enemy is damaged and flies away from the ship
+    Flee,
+    /// The enemy [flocks](flock) with its swarm
+    Flock,
+}
+
+impl Behaviour {
+    /// Chooses the behaviour of an enemy for the current tick.
+    pub fn choose(enemy: &Enemy, game_settings: &GameSettings) -> Self {
+        if enemy.swarms(game_settings) {
+            return Behaviour::Flock;
+        }
+        if !enemy.hunts(game_settings) {
+            return Behaviour::Drift;
+        }
+        if enemy.health < enemy.max_health {
+            return Behaviour::Flee;
+        }
+
+        let (offset_x, offset_y) = offset_to_ship(enemy, game_settings);
+        let ship = &game_settings.ship;
+        if offset_x.powi(2) + offset_y.powi(2) <= game_settings.enemy_orbit_radius.powi(2) {
+            Behaviour::Orbit
+        } else if ship.vx == 0.0 && ship.vy == 0.0 {
+            Behaviour::Seek
+        } else {
+            Behaviour::Pursue
+        }
+    }
+
+    /// Returns the direction the behaviour wants the enemy to fly in. The direction is not normalized.
+    ///
+    /// Returns none if the enemy doesn't steer on its own.
+    pub fn desired_direction(
+        self,
+        enemy: &Enemy,
+        game_settings: &GameSettings,
+    ) -> Option<(f32, f32)> {
+        let (offset_x, offset_y) = offset_to_ship(enemy, game_settings);
+        match self {
+            Behaviour::Drift | Behaviour::Flock => None,
+            Behaviour::Seek => Some((offset_x, offset_y)),
+            Behaviour::Pursue => {
+                let ship = &game_settings.ship;
+                let distance = (offset_x.powi(2) + offset_y.powi(2)).sqrt();
+                let speed = (enemy.dx.powi(2) + enemy.dy.powi(2)).sqrt();
+                let lead = if speed > 0.0 {
+                    (distance / speed).min(MAX_LEAD_TIME)
+                } else {
+                    0.0
+                };
+                Some((offset_x + ship.vx * lead, offset_y + ship.vy * lead))
+            }
+            Behaviour::Orbit => {
+                let distance = (offset_x.powi(2) + offset_y.powi(2)).sqrt();
+                if distance == 0.0 {
+                    return None;
+                }
+                let (radial_x, radial_y) = (offset_x / distance, offset_y / distance);
+                // Keep circling in the direction the enemy already flies around the ship
+                let clockwise = enemy.dx * radial_y - enemy.dy * radial_x > 0.0;
+                let (tangent_x, tangent_y) = if clockwise {
+                    (radial_y, -radial_x)
+                } else {
+                    (-radial_y, radial_x)
+                };
+                // Drift back to the orbit radius when the enemy is too close or too far away
+                let correction = (distance - game_settings.enemy_orbit_radius)
+                    / game_settings.enemy_orbit_radius;
+                Some((
+                    tangent_x + radial_x * correction,
+                    tangent_y + radial_y * correction,
+                ))
+            }
+            Behaviour::Flee => Some((-offset_x, -offset_y)),
+        }
+    }
+}
+
+/// Returns the offset from the enemy to the ship. On a toroidal field this is the shortest way, which may lead across the edges.
+fn offset_to_ship(enemy: &Enemy, game_settings: &GameSettings) -> (f32, f32) {
+    let position = enemy.get_center();
+    let (ship_x, ship_y) = game_settings
+        .field
+        .nearest_image(position, game_settings.ship.get_position());
+    (ship_x - position.0, ship_y - position.1)
+}
+
+/// Chooses the behaviour of an enemy and turns it towards the direction the behaviour asks for.
+///
+/// The enemy keeps its speed and turns by at most the [turn rate](GameSettings::enemy_turn_rate) weighted by the visibility of the ship.
+pub fn steer(enemy: &mut Enemy, game_settings: &GameSettings) {
+    enemy.behaviour = Behaviour::choose(enemy, game_settings);
+    let (desired_x, desired_y) = match enemy.behaviour.desired_direction(enemy, game_settings) {
+        Some(direction) => direction,
+        None => return,
+    };
+
+    let visibility = (game_settings.ship_visibility / 10.0).clamp(0.0, 1.0);
+    let max_turn = game_settings.enemy_turn_rate * visibility * TICK_DURATION;
+    let speed = (enemy.dx.powi(2) + enemy.dy.powi(2)).sqrt();
+    if max_turn <= 0.0 || speed == 0.0 || (desired_x == 0.0 && desired_y == 0.0) {
+        return;
+    }
+
+    let angle = enemy.dy.atan2(enemy.dx);
+    // The difference between the angles, wrapped to the shorter way around
+    let turn = (desired_y.atan2(desired_x) - angle + PI).rem_euclid(2.0 * PI) - PI;
+    let angle = angle + turn.clamp(-max_turn, max_turn);
+    enemy.dx = angle.cos() * speed;
+    enemy.dy = angle.sin() * speed;
+}
+
+/// Applies the rules of the boids to all enemies that [swarm](Enemy::swarms).
+///
+/// Every enemy of a swarm looks at the other swarming enemies within the [perception](GameSettings::swarm_perception) and combines four wishes:
+/// separation from neighbours that are too close, alignment with their direction, cohesion towards their center and attraction towards the ship, which is weighted by its visibility.
+/// It then turns towards the combined direction while it keeps its speed. All enemies decide based on where the swarm was at the start of the tick, so the order of the enemies doesn't matter.
+///
+/// Swarms are small, so every swarming enemy simply looks at all others.
+pub fn flock(enemies: &mut [Enemy], game_settings: &GameSettings) {
+    let field = &game_settings.field;
+    let swarm: Vec<usize> = (0..enemies.len())
+        .filter(|&i| enemies[i].swarms(game_settings))
+        .collect();
+    let visibility = (game_settings.ship_visibility / 10.0).clamp(0.0, 1.0);
+
+    let directions: Vec<(usize, (f32, f32))> = swarm
+        .iter()
+        .map(|&i| {
+            let enemy = &enemies[i];
+            let position = enemy.get_center();
+            let (mut separation, mut alignment, mut cohesion) =
+                ((0.0, 0.0), (0.0, 0.0), (0.0, 0.0));
+            let mut neighbours = 0;
+            for &j in swarm.iter().filter(|&&j| j != i) {
+                let other = &enemies[j];
+                let (other_x, other_y) = field.nearest_image(position, other.get_center());
+                let (offset_x, offset_y) = (other_x - position.0, other_y - position.1);
+                let distance = (offset_x.powi(2) + offset_y.powi(2)).sqrt();
+                if distance > game_settings.swarm_perception {
+                    continue;
+                }
+                neighbours += 1;
+                alignment = (alignment.0 + other.dx, alignment.1 + other.dy);
+                cohesion = (cohesion.0 + offset_x, cohesion.1 + offset_y);
+                if distance > 0.0 && distance < game_settings.swarm_separation {
+                    // Push away harder the closer the neighbour is
+                    let push = (game_settings.swarm_separation - distance) / distance;
+                    separation = (
+                        separation.0 - offset_x * push,
+                        separation.1 - offset_y * push,
+                    );
+                }
+            }
+
+            let mut desired = scale(
+                normalize(offset_to_ship(enemy, game_settings)),
+                ATTRACTION_WEIGHT * visibility,
+            );
+            if neighbours > 0 {
+                for (wish, weight) in [
+                    (separation, SEPARATION_WEIGHT),
+                    (alignment, ALIGNMENT_WEIGHT),
+                    (cohesion, COHESION_WEIGHT),
+                ] {
+                    let (x, y) = scale(normalize(wish), weight);
+                    desired = (desired.0 + x, desired.1 + y);
+                }
+            }
+            (i, desired)
+        })
+        .collect();
+
+    let agility = (SWARM_AGILITY * TICK_DURATION).min(1.0);
+    for (i, desired) in directions {
+        let enemy = &mut enemies[i];
+        enemy.behaviour = Behaviour::Flock;
+        let speed = (enemy.dx.powi(2) + enemy.dy.powi(2)).sqrt();
+        let (desired_x, desired_y) = scale(normalize(desired), speed);
+        let (dx, dy) = (
+            enemy.dx + (desired_x - enemy.dx) * agility,
+            enemy.dy + (desired_y - enemy.dy) * agility,
+        );
+        (enemy.dx, enemy.dy) = scale(normalize((dx, dy)), speed);
+    }
+}
+
+/// Returns the vector with a length of 1, or the zero vector if it has no length.
+fn normalize((x, y): (f32, f32)) -> (f32, f32) {
+    let length = (x.powi(2) + y.powi(2)).sqrt();
+    if length == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (x / length, y / length)
+    }
+}
+
+/// Returns the vector multiplied by the factor.
+fn scale((x, y): (f32, f32), factor: f32) -> (f32, f32) {
+    (x * factor, y * factor)
+}
@@ -0,0 +1,162 @@
+//! This module contains the verification of submitted games.
+//!
+//! A client submits its [game ressource](GameRessource) together with the [replay](Replay) of the run. The replay is re-simulated with the very same model and the claimed score, coins, lives and item levels are compared to the result.
+
+use serde::Serialize;
+
+use super::{
+    game::{GameAction, GameState, TICKS_PER_SECOND, TICK_DURATION},
+    game_ressources::GameRessource,
+    replay::{Replay, ReplayError},
+};
+
+/// The maximum difference between the claimed and the simulated score. Scores are summed up floats, so they are not compared exactly.
+const SCORE_TOLERANCE: f64 = 1e-6;
+
+/// The maximum number of ticks a submitted replay may have. This is ten hours of play, which keeps the re-simulation of a single submission bounded.
+pub const MAX_TICKS: u32 = 10 * 60 * 60 * TICKS_PER_SECOND;
+
+/// The errors that prevent a submitted game from being verified. Those are rejected before the replay is simulated.
+#[derive(Debug, PartialEq)]
+pub enum VerificationError {
+    /// The replay can't be played by this version of the game
+    Replay(ReplayError),
+    /// The replay has more ticks than [MAX_TICKS]
+    TooLong(u32),
+    /// The replay doesn't have enough ticks to reach the claimed score, as the score rises by a fixed amount per tick
+    ImplausibleScore,
+}
+
+impl From<ReplayError> for VerificationError {
+    fn from(replay_error: ReplayError) -> Self {
+        VerificationError::Replay(replay_error)
+    }
+}
+
+/// The comparison of a claimed value with the simulated value.
+#[derive(Serialize)]
+pub struct Check<T> {
+    /// The value claimed by the client
+    pub claimed: T,
+    /// The value reached by the simulation
+    pub simulated: T,
+    /// Whether the claimed value was reached
+    pub valid: bool,
+}
+
+/// The comparison of the claimed level of an item with the simulated level.
+#[derive(Serialize)]
+pub struct ItemCheck {
+    /// The id of the item
+    pub id: u32,
+    /// The level claimed by the client
+    pub claimed: u32,
+    /// The level reached by the simulation
+    pub simulated: u32,
+    /// Whether the claimed level was reached
+    pub valid: bool,
+}
+
+/// The result of the verification of a submitted game.
+#[derive(Serialize)]
+pub struct VerificationReport {
+    /// Whether the whole claim is reachable
+    pub valid: bool,
+    /// The recorded actions a player can't take (e.g. buying items without paying for them) as (tick, action)
+    pub forbidden_actions: Vec<(u32, GameAction)>,
+    pub score: Check<f64>,
+    pub coins: Check<i32>,
+    pub lives: Check<i32>,
+    pub ended: Check<bool>,
+    pub items: Vec<ItemCheck>,
+}
+
+impl<T: PartialEq> Check<T> {
+    /// Compares the claimed value with the simulated value.
+    fn new(claimed: T, simulated: T) -> Self {
+        let valid = claimed == simulated;
+        Self {
+            claimed,
+            simulated,
+            valid,
+        }
+    }
+}
+
+/// Re-simulates the replay and checks whether the claimed game is reached by it.
+///
+/// Replays of another version of the replay format, replays longer than [MAX_TICKS] and replays too short to reach the claimed score are rejected without simulating them.
+pub fn verify(
+    replay: &Replay,
+    claim: &GameRessource,
+) -> Result<VerificationReport, VerificationError> {
+    replay.check_version()?;
+    if replay.ticks > MAX_TICKS {
+        return Err(VerificationError::TooLong(replay.ticks));
+    }
+    let max_score =
+        replay.ticks as f64 * replay.new_game().game_settings.score_increase * TICK_DURATION as f64;
+    if claim.score > max_score + SCORE_TOLERANCE {
+        return Err(VerificationError::ImplausibleScore);
+    }
+
+    let forbidden_actions: Vec<(u32, GameAction)> = replay
+        .inputs
+        .iter()
+        .filter(|(_, action)| matches!(action, GameAction::BuyItem(_, true)))
+        .cloned()
+        .collect();
+
+    let game = replay.play()?;
+
+    let score = Check {
+        claimed: claim.score,
+        simulated: game.score,
+        valid: (claim.score - game.score).abs() <= SCORE_TOLERANCE,
+    };
+    let coins = Check::new(claim.coins, game.coins);
+    let lives = Check::new(claim.lives, game.lives);
+    let ended = Check::new(claim.ended, game.state == GameState::NotRunning);
+
+    let items: Vec<ItemCheck> = game
+        .shop
+        .items
+        .iter()
+        .map(|item| {
+            let claimed = claim
+                .items
+                .iter()
+                .filter(|item_level| item_level.item.id == item.id)
+                .map(|item_level| item_level.level)
+                .sum();
+            ItemCheck {
+                id: item.id,
+                claimed,
+                simulated: item.level as u32,
+                valid: claimed == item.level as u32,
+            }
+        })
+        .collect();
+    let unknown_items = claim
+        .items
+        .iter()
+        .any(|item_level| !items.iter().any(|item| item.id == item_level.item.id));
+
+    let valid = forbidden_actions.is_empty()
+        && !unknown_items
+        && score.valid
+        && coins.valid
+        && lives.valid
+        && ended.valid
+        && items.iter().all(|item| item.valid);
+
+    Ok(VerificationReport {
+        valid,
+        forbidden_actions,
+        score,
+        coins,
+        lives,
+        ended,
+        items,
+    })
+}
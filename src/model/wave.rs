@@ -0,0 +1,176 @@
+//! Enemies arrive in numbered waves.
+//!
+//! Every wave consists of groups of enemies of the same kind that enter the field from the same edge. Once all enemies of a wave were spawned and the field is clear,
+//! the game enters an [intermission](super::game::GameState::Intermission) in which the player can shop before the next wave starts.
+//!
+//! The first waves are authored by hand in `waves.json`, which is bundled with the game like the [archetype catalog](crate::archetype).
+//! Later waves repeat the last authored wave with more and faster enemies.
+
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use super::archetype::{Archetype, CatalogError};
+
+/// The hand made waves that are bundled with the game.
+const WAVES: &str = include_str!("waves.json");
+
+/// The edge of the field enemies enter from.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum Edge {
+    /// A random edge for every enemy
+    #[default]
+    Any,
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// A group of enemies of the same kind in a [wave](Wave). Missing fields default to those of a single enemy whose archetype is chosen by the score.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
+pub struct WaveGroup {
+    /// The name of the [archetype](crate::archetype::Archetype) of the enemies. If this is none the archetype is chosen by the score like before waves existed
+    pub enemy_type: Option<String>,
+    /// The number of enemies in the group
+    pub count: u32,
+    /// The number of enemies that spawn together whenever the [spawn timeout](super::game_settings::GameSettings::enemy_spawn_timeout) ran out
+    pub burst: u32,
+    /// The smallest and largest radius of the enemies. If this is none the radius of the archetype is used
+    pub radius: Option<(f32, f32)>,
+    /// The speed of the enemies as a multiple of the speed of their archetype
+    pub speed: f32,
+    /// The edge the enemies enter from
+    pub edge: Edge,
+}
+
+impl WaveGroup {
+    /// Creates a group of enemies of the given archetype with the size and speed of the archetype.
+    pub fn new(enemy_type: Option<&str>, count: u32) -> Self {
+        Self {
+            enemy_type: enemy_type.map(String::from),
+            count,
+            burst: 1,
+            radius: None,
+            speed: 1.0,
+            edge: Edge::Any,
+        }
+    }
+}
+
+impl Default for WaveGroup {
+    fn default() -> Self {
+        Self::new(None, 1)
+    }
+}
+
+/// A numbered wave of enemies.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Wave {
+    /// The number of the wave, starting with 1
+    pub number: u32,
+    /// The groups of enemies in the wave. They are spawned one after the other
+    pub groups: Vec<WaveGroup>,
+    /// The number of enemies of the wave that were spawned so far
+    pub spawned: u32,
+}
+
+impl Default for Wave {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Wave {
+    /// Returns the groups of the hand made waves. They are only parsed once.
+    pub fn authored() -> &'static [Vec<WaveGroup>] {
+        static AUTHORED: OnceCell<Vec<Vec<WaveGroup>>> = OnceCell::new();
+        AUTHORED.get_or_init(|| {
+            let waves: Vec<Vec<WaveGroup>> =
+                serde_json::from_str(WAVES).expect("The bundled waves are valid");
+            Wave::validate_waves(&waves, Archetype::catalog())
+                .expect("The bundled waves only use archetypes of the catalog");
+            waves
+        })
+    }
+
+    /// Checks that every group of the waves that names an archetype names one of the archetypes.
+    pub fn validate_waves(
+        waves: &[Vec<WaveGroup>],
+        archetypes: &[Archetype],
+    ) -> Result<(), CatalogError> {
+        for name in waves
+            .iter()
+            .flatten()
+            .filter_map(|group| group.enemy_type.as_ref())
+        {
+            if archetypes.iter().all(|archetype| &archetype.name != name) {
+                return Err(CatalogError::UnknownWaveArchetype(name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the wave with the given number.
+    pub fn new(number: u32) -> Self {
+        let number = number.max(1);
+        let waves = Wave::authored();
+        let authored = waves.len() as u32;
+        let groups = if number <= authored {
+            waves[number as usize - 1].clone()
+        } else {
+            // Every wave after the authored ones has half the enemies of the last authored wave more, which are 10% faster
+            let extra = (number - authored) as f32;
+            waves
+                .last()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|group| WaveGroup {
+                    count: group.count + (group.count as f32 * extra / 2.0).ceil() as u32,
+                    speed: group.speed * (1.0 + extra * 0.1),
+                    ..group
+                })
+                .collect()
+        };
+        Self {
+            number,
+            groups,
+            spawned: 0,
+        }
+    }
+
+    /// Returns the number of enemies in the wave.
+    pub fn total(&self) -> u32 {
+        self.groups.iter().map(|group| group.count).sum()
+    }
+
+    /// Returns true if all enemies of the wave were spawned.
+    pub fn is_spawned(&self) -> bool {
+        self.spawned >= self.total()
+    }
+
+    /// Returns the fraction of the enemies of the wave that were spawned so far, between 0 and 1.
+    pub fn progress(&self) -> f32 {
+        match self.total() {
+            0 => 1.0,
+            total => self.spawned.min(total) as f32 / total as f32,
+        }
+    }
+
+    /// Returns the group of the next enemies to spawn and how many of them spawn now. The enemies are counted as spawned.
+    ///
+    /// Returns none if all enemies of the wave were spawned.
+    pub fn next_burst(&mut self) -> Option<(WaveGroup, u32)> {
+        let mut before = 0;
+        for group in &self.groups {
+            if self.spawned < before + group.count {
+                let count = group.burst.max(1).min(before + group.count - self.spawned);
+                self.spawned += count;
+                return Some((group.clone(), count));
+            }
+            before += group.count;
+        }
+        None
+    }
+}
@@ -0,0 +1,252 @@
+mod common;
+
+use common::{enemy, projectile, quiet_game};
+use rand::SeedableRng;
+use wasm_asteroids::{
+    archetype::{Archetype, ArchetypeBehaviour, CatalogError, SpawnWeight, SplitRule},
+    enemy::Enemy,
+    game::{Game, GameAction, GameRng},
+    game_ressources::LoadError,
+    game_settings::GameSettings,
+    id_generator::IdGenerator,
+    wave::WaveGroup,
+};
+
+/// A hunting archetype that isn't part of the bundled catalog.
+fn comet() -> Archetype {
+    Archetype {
+        name: "Comet".to_string(),
+        radius: (30.0, 31.0),
+        health: (3, 4),
+        health_per_radius: 0.0,
+        health_per_1000_score: 0.0,
+        armor: (0, 0),
+        speed: 2.0,
+        coins: 7,
+        behaviour: ArchetypeBehaviour::Hunt,
+        spawn_weight: SpawnWeight {
+            start: 0.0,
+            end: 0.0,
+            score: 0.0,
+        },
+        split: Some(SplitRule {
+            reproduce_limit: (5.0, 6.0),
+            children: (4, 4),
+            child: Some("Asteroid".to_string()),
+        }),
+    }
+}
+
+#[test]
+fn the_bundled_catalog_is_consistent() {
+    assert_eq!(Archetype::validate_catalog(Archetype::catalog()), Ok(()));
+}
+
+#[test]
+fn inconsistent_catalogs_are_rejected() {
+    let validate = |archetypes: Vec<Archetype>| Archetype::validate_catalog(&archetypes);
+    let comet_named = |name: &str| Archetype {
+        name: name.to_string(),
+        ..comet()
+    };
+
+    assert_eq!(validate(vec![]), Err(CatalogError::Empty));
+    assert_eq!(
+        validate(vec![comet_named("Asteroid"), comet(), comet()]),
+        Err(CatalogError::DuplicateName("Comet".to_string()))
+    );
+    assert_eq!(
+        validate(vec![comet()]),
+        Err(CatalogError::UnknownChild("Comet".to_string()))
+    );
+    let reversed = Archetype {
+        radius: (31.0, 30.0),
+        ..comet()
+    };
+    assert_eq!(
+        validate(vec![comet_named("Asteroid"), reversed]),
+        Err(CatalogError::UnorderedRange("Comet".to_string()))
+    );
+}
+
+#[test]
+fn the_archetype_is_chosen_by_the_score() {
+    let settings = GameSettings::new();
+    let mut rng = GameRng::seed_from_u64(22);
+    let names = |score: f64, rng: &mut GameRng| -> Vec<String> {
+        (0..200)
+            .map(|id| Enemy::new(&settings, score, id, rng).enemy_type)
+            .collect()
+    };
+
+    assert!(names(0.0, &mut rng).iter().all(|name| name == "Asteroid"));
+    let late = names(1000.0, &mut rng);
+    assert!(late.iter().any(|name| name == "Ship"));
+    assert!(late.iter().all(|name| name != "Debris"));
+}
+
+#[test]
+fn new_archetypes_can_be_added_without_code() {
+    let mut settings = GameSettings::new();
+    settings.archetypes.push(comet());
+    let mut rng = GameRng::seed_from_u64(22);
+
+    let enemy = Enemy::from_group(
+        &settings,
+        &WaveGroup::new(Some("Comet"), 1),
+        0.0,
+        1000,
+        &mut rng,
+    );
+    assert_eq!(enemy.enemy_type, "Comet");
+    assert!(enemy.radius >= 30.0 && enemy.radius <= 31.0);
+    assert!(enemy.health == 3 || enemy.health == 4);
+    assert!(enemy.hunts(&settings));
+    assert_eq!(enemy.get_coins(&settings), 7);
+
+    let mut dying = enemy.clone();
+    dying.health = 1;
+    let children = dying.take_damage(&settings, 1, 0.0, &IdGenerator::default(), &mut rng);
+    assert_eq!(children.len(), 4);
+    assert!(children.iter().all(|child| child.enemy_type == "Asteroid"));
+}
+
+#[test]
+fn ranges_may_have_equal_bounds() {
+    let mut settings = GameSettings::new();
+    settings.archetypes.push(Archetype {
+        radius: (30.0, 30.0),
+        split: Some(SplitRule {
+            reproduce_limit: (5.0, 5.0),
+            ..comet().split.unwrap()
+        }),
+        ..comet()
+    });
+    let mut rng = GameRng::seed_from_u64(22);
+
+    let enemy = Enemy::from_group(
+        &settings,
+        &WaveGroup::new(Some("Comet"), 1),
+        0.0,
+        1000,
+        &mut rng,
+    );
+    assert_eq!(enemy.radius, 30.0);
+
+    let group = WaveGroup {
+        radius: Some((12.0, 12.0)),
+        ..WaveGroup::new(Some("Comet"), 1)
+    };
+    let enemy = Enemy::from_group(&settings, &group, 0.0, 1001, &mut rng);
+    assert_eq!(enemy.radius, 12.0);
+}
+
+#[test]
+fn health_grows_with_radius_and_score() {
+    let mut rng = GameRng::seed_from_u64(23);
+    let comet = Archetype {
+        health: (2, 2),
+        health_per_radius: 0.1,
+        health_per_1000_score: 2.0,
+        ..comet()
+    };
+    assert_eq!(comet.roll_health(10.0, 0.0, &mut rng), 3);
+    assert_eq!(comet.roll_health(30.0, 0.0, &mut rng), 5);
+    assert_eq!(comet.roll_health(30.0, 1500.0, &mut rng), 8);
+}
+
+#[test]
+fn armor_absorbs_damage_until_it_cracks() {
+    let mut settings = GameSettings::new();
+    settings.archetypes.push(comet());
+    let generator = IdGenerator::default();
+    let mut rng = GameRng::seed_from_u64(23);
+    let mut enemy = Enemy::from_group(
+        &settings,
+        &WaveGroup::new(Some("Comet"), 1),
+        0.0,
+        1000,
+        &mut rng,
+    );
+    enemy.health = 2;
+    enemy.armor = 2;
+
+    // The first hits only crack the armor
+    for armor in [1, 0] {
+        enemy = enemy.take_damage(&settings, 1, 0.0, &generator, &mut rng)[0].clone();
+        assert_eq!((enemy.health, enemy.armor), (2, armor));
+    }
+    enemy = enemy.take_damage(&settings, 1, 0.0, &generator, &mut rng)[0].clone();
+    assert_eq!(enemy.health, 1);
+
+    // The enemy only splits once its health is depleted
+    let children = enemy.take_damage(&settings, 1, 0.0, &generator, &mut rng);
+    assert_eq!(children.len(), 4);
+    assert!(children.iter().all(|child| child.health >= 1));
+}
+
+#[test]
+fn coins_are_only_dropped_once_the_enemy_is_destroyed() {
+    let mut game = quiet_game(23);
+    let mut armored = enemy(20.0, (300.0, 300.0), (0.0, 0.0));
+    armored.health = 1;
+    armored.armor = 1;
+    game.enemies = vec![armored];
+
+    // The first hit only cracks the armor
+    game.projectiles = vec![projectile((300.0, 300.0), (0.0, 0.0))];
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.enemies.len(), 1);
+    assert_eq!(game.coins, 0);
+
+    game.projectiles = vec![projectile((300.0, 300.0), (0.0, 0.0))];
+    let _ = game.step(GameAction::Tick);
+    assert!(game.enemies.iter().all(|enemy| enemy.id != 1000));
+    assert!(game.coins > 0);
+}
+
+#[test]
+fn saves_with_unknown_archetypes_are_rejected() {
+    let mut game = Game::new_with_seed(22);
+    let mut enemy = Enemy::new(
+        &game.game_settings,
+        0.0,
+        1000,
+        &mut GameRng::seed_from_u64(0),
+    );
+    enemy.enemy_type = "Comet".to_string();
+    game.enemies = vec![enemy];
+
+    assert_eq!(
+        game.snapshot().validate(),
+        Err(LoadError::UnknownArchetype("Comet".to_string()))
+    );
+}
+
+#[test]
+fn saves_keep_the_archetypes_they_were_created_with() {
+    // The bundled catalog may have changed since the game was saved
+    let mut game = Game::new_with_seed(22);
+    game.game_settings.archetypes.push(comet());
+    let mut enemy = Enemy::new(
+        &game.game_settings,
+        0.0,
+        1000,
+        &mut GameRng::seed_from_u64(0),
+    );
+    enemy.enemy_type = "Comet".to_string();
+    game.enemies = vec![enemy];
+
+    assert_eq!(game.snapshot().validate(), Ok(()));
+}
+
+#[test]
+fn saves_without_archetypes_are_rejected() {
+    let mut game = Game::new_with_seed(22);
+    game.game_settings.archetypes.clear();
+
+    assert_eq!(
+        game.snapshot().validate(),
+        Err(LoadError::InvalidArchetypes(CatalogError::Empty))
+    );
+}
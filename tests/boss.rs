@@ -0,0 +1,159 @@
+mod common;
+
+use common::{projectile, quiet_game};
+use wasm_asteroids::{
+    boss::{Boss, WEAK_POINT_MULTIPLIER},
+    game::{Game, GameAction, TICK_DURATION},
+    game_event::GameEvent,
+    game_settings::GameSettings,
+    id_generator::IdGenerator,
+    positioned::Positioned,
+    projectile::{Faction, Projectile},
+};
+
+/// Creates a game with a boss that has arrived on the field and won't attack.
+fn boss_game() -> Game {
+    let mut game = quiet_game(20);
+    let mut boss = Boss::new(&game.game_settings, 0, 5000);
+    boss.set_position(300.0, 400.0);
+    boss.dx = 0.0;
+    boss.dy = 0.0;
+    boss.time_until_attack = f32::MAX;
+    game.boss = Some(boss);
+    game.next_boss = 1;
+    game
+}
+
+/// Creates a resting projectile that is small enough to hit a single weak point.
+fn resting_projectile(position: (f32, f32)) -> Projectile {
+    Projectile {
+        radius: 4.0,
+        ..projectile(position, (0.0, 0.0))
+    }
+}
+
+#[test]
+fn a_boss_appears_at_the_boss_score_and_stops_the_spawning() {
+    let mut game = quiet_game(20);
+    game.time_until_enemy_spawn = 0.0;
+    game.score = game.game_settings.boss_scores[0];
+
+    let _ = game.step(GameAction::Tick);
+    let boss = game.boss.clone().unwrap();
+    assert_eq!(boss.max_health, game.game_settings.boss_health);
+    assert_eq!(game.next_boss, 1);
+    assert!(game.enemies.is_empty());
+    assert!(game.take_events().contains(&GameEvent::BossSpawned {
+        id: boss.id,
+        max_health: boss.max_health
+    }));
+
+    for _ in 0..100 {
+        let _ = game.step(GameAction::Tick);
+    }
+    assert!(game.enemies.is_empty());
+    assert_eq!(game.time_until_enemy_spawn, 0.0);
+}
+
+#[test]
+fn the_boss_flies_onto_the_field_before_it_attacks() {
+    let mut game = quiet_game(20);
+    game.score = game.game_settings.boss_scores[0];
+    let _ = game.step(GameAction::Tick);
+    let field = game.game_settings.field;
+
+    let mut ticks = 0;
+    while game.boss.as_ref().unwrap().is_entering(&field) {
+        assert!(game.projectiles.is_empty());
+        let _ = game.step(GameAction::Tick);
+        ticks += 1;
+    }
+    let boss = game.boss.as_ref().unwrap();
+    assert!(boss.y + boss.radius < field.height as f32);
+    assert!(ticks as f32 * TICK_DURATION < 5.0);
+
+    let _ = game.step(GameAction::Tick);
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.projectiles.len(), 1);
+    assert_eq!(game.projectiles[0].faction, Faction::Hostile);
+}
+
+#[test]
+fn weak_points_take_more_damage() {
+    let mut game = boss_game();
+    let boss = game.boss.clone().unwrap();
+    game.projectiles = vec![resting_projectile(boss.get_center())];
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.boss.as_ref().unwrap().health, boss.max_health - 1);
+
+    let point = game.boss.as_ref().unwrap().weak_points[0];
+    game.projectiles = vec![resting_projectile(point.get_position())];
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(
+        game.boss.as_ref().unwrap().health,
+        boss.max_health - 1 - WEAK_POINT_MULTIPLIER
+    );
+}
+
+#[test]
+fn the_boss_changes_phases_with_its_health() {
+    let mut game = boss_game();
+    let max_health = game.boss.as_ref().unwrap().max_health;
+    assert_eq!(game.boss.as_ref().unwrap().phase(), 1);
+
+    game.boss.as_mut().unwrap().health = max_health * 2 / 3 + 1;
+    let center = game.boss.as_ref().unwrap().get_center();
+    game.projectiles = vec![resting_projectile(center)];
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.boss.as_ref().unwrap().phase(), 2);
+    assert!(game
+        .take_events()
+        .contains(&GameEvent::BossPhaseChanged { id: 5000, phase: 2 }));
+
+    game.boss.as_mut().unwrap().health = max_health / 3;
+    assert_eq!(game.boss.as_ref().unwrap().phase(), 3);
+}
+
+#[test]
+fn every_phase_has_its_own_attack() {
+    let settings = GameSettings::new();
+    let ids = IdGenerator::default();
+    let mut boss = Boss::new(&settings, 0, 5000);
+    boss.set_position(300.0, 400.0);
+
+    let shots: Vec<usize> = [1, 2, 3]
+        .iter()
+        .map(|phase| {
+            boss.health = boss.max_health * (4 - phase) / 3;
+            boss.time_until_attack = 0.0;
+            boss.attack(&settings, &ids).len()
+        })
+        .collect();
+    assert_eq!(shots, vec![1, 5, 2]);
+}
+
+#[test]
+fn defeating_the_boss_pays_out_coins() {
+    let mut game = boss_game();
+    game.boss.as_mut().unwrap().health = 1;
+    let center = game.boss.as_ref().unwrap().get_center();
+    game.projectiles = vec![resting_projectile(center)];
+
+    let _ = game.step(GameAction::Tick);
+    let coins = game.game_settings.boss_coins * game.game_settings.coin_multiplier;
+    assert!(game.boss.is_none());
+    assert_eq!(game.coins, coins);
+    assert!(game.take_events().iter().any(
+        |event| matches!(event, GameEvent::BossDefeated { id: 5000, coins: c, .. } if *c == coins)
+    ));
+}
+
+#[test]
+fn the_boss_survives_a_save() {
+    let mut game = boss_game();
+    game.boss.as_mut().unwrap().health = 7;
+
+    let restored = Game::from_snapshot(game.snapshot());
+    assert_eq!(restored.boss, game.boss);
+    assert_eq!(restored.next_boss, 1);
+}
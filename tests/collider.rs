@@ -0,0 +1,94 @@
+use rand::SeedableRng;
+use wasm_asteroids::{
+    collider::{convex_hull, Collider},
+    enemy::Enemy,
+    game::GameRng,
+    game_settings::{field::Field, ship::Ship, GameSettings},
+    positioned::Positioned,
+};
+
+fn triangle() -> Collider {
+    Collider::Polygon(vec![(-10.0, -10.0), (10.0, -10.0), (0.0, 10.0)])
+}
+
+#[test]
+fn polygons_collide_only_if_no_axis_separates_them() {
+    let square = Collider::rectangle(20.0, 20.0);
+
+    assert!(square.intersects((0.0, 0.0), &triangle(), (15.0, 0.0)));
+    assert!(!square.intersects((0.0, 0.0), &triangle(), (25.0, 0.0)));
+    // The bounding boxes overlap, but the slanted edge of the triangle separates them
+    assert!(!square.intersects((0.0, 0.0), &triangle(), (18.0, -16.0)));
+}
+
+#[test]
+fn circles_miss_the_corners_of_polygons() {
+    let square = Collider::rectangle(20.0, 20.0);
+    let circle = Collider::Circle(5.0);
+
+    assert!(square.intersects((0.0, 0.0), &circle, (14.0, 0.0)));
+    // Inside the bounding box of the square, but outside its corner
+    assert!(!square.intersects((0.0, 0.0), &circle, (14.0, 14.0)));
+    assert!(square.intersects((0.0, 0.0), &circle, (13.0, 13.0)));
+}
+
+#[test]
+fn penetration_points_towards_the_other_collider() {
+    let square = Collider::rectangle(20.0, 20.0);
+    let penetration = square
+        .penetration((0.0, 0.0), &square, (-15.0, 2.0))
+        .unwrap();
+
+    assert_eq!(penetration.normal, (-1.0, 0.0));
+    assert_eq!(penetration.depth, 5.0);
+}
+
+#[test]
+fn convex_hull_drops_inner_points() {
+    let hull = convex_hull(vec![
+        (0.0, 0.0),
+        (10.0, 0.0),
+        (5.0, 2.0),
+        (10.0, 10.0),
+        (0.0, 10.0),
+        (5.0, 5.0),
+    ]);
+
+    assert_eq!(
+        hull,
+        vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]
+    );
+}
+
+#[test]
+fn enemy_outlines_are_convex_and_fit_the_radius() {
+    let mut rng = GameRng::seed_from_u64(3);
+    let settings = GameSettings::new();
+    for id in 0..50 {
+        let enemy = Enemy::new(&settings, 0.0, id, &mut rng);
+        assert!(enemy.outline.len() >= 3);
+        assert_eq!(convex_hull(enemy.outline.clone()), enemy.outline);
+        assert!(enemy
+            .outline
+            .iter()
+            .all(|(x, y)| (x * x + y * y).sqrt() <= 1.0));
+    }
+}
+
+#[test]
+fn ship_collider_turns_with_the_ship() {
+    let mut ship = Ship::new(&Field::new(1000, 1000));
+    let (x, y) = ship.get_center();
+    let probe = Collider::Circle(2.0);
+
+    // The ship faces right, so its tip is right of the center
+    ship.dx = 1.0;
+    assert!(ship.collider().intersects((x, y), &probe, (x + 25.0, y)));
+    assert!(!ship.collider().intersects((x, y), &probe, (x - 32.0, y)));
+
+    // The direction of the ship points down the screen, which is down in the field
+    ship.dx = 0.0;
+    ship.dy = 1.0;
+    assert!(ship.collider().intersects((x, y), &probe, (x, y - 25.0)));
+    assert!(!ship.collider().intersects((x, y), &probe, (x, y + 32.0)));
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::{enemy, projectile};
+use wasm_asteroids::{
+    enemy::Enemy,
+    game::{Game, GameAction, TICK_DURATION},
+    game_settings::field::Field,
+    positioned::{Moves, Positioned},
+    projectile::Projectile,
+};
+
+/// Creates a small resting enemy at the given position.
+fn debris(x: f32, y: f32) -> Enemy {
+    enemy(5.0, (x, y), (0.0, 0.0))
+}
+
+/// Creates a fast projectile that moved from `from` to its current position in the last tick.
+fn fast_projectile(from: (f32, f32), dx: f32) -> Projectile {
+    projectile((from.0 + dx * TICK_DURATION, from.1), (dx, 0.0))
+}
+
+#[test]
+fn swept_collision_detects_tunneling() {
+    let enemy = debris(200.0, 100.0);
+    let projectile = fast_projectile((170.0, 100.0), 3000.0);
+
+    assert!(!projectile.is_collision(&enemy, &Field::new(1000, 1000)));
+    assert!(projectile.is_swept_collision(&enemy, &Field::new(1000, 1000)));
+}
+
+#[test]
+fn swept_collision_ignores_misses() {
+    let enemy = debris(200.0, 130.0);
+    let projectile = fast_projectile((170.0, 100.0), 3000.0);
+
+    assert!(!projectile.is_swept_collision(&enemy, &Field::new(1000, 1000)));
+}
+
+#[test]
+fn swept_collision_detects_tangential_hits() {
+    let field = Field::new(1000, 1000);
+    // The projectile passes the enemy between the points a sampled test would check
+    let projectile = fast_projectile((170.0, 100.0), 3000.0);
+
+    let mut round = debris(200.0, 114.5);
+    round.outline = vec![];
+    assert!(projectile.is_swept_collision(&round, &field));
+    round.set_position(200.0, 115.5);
+    assert!(!projectile.is_swept_collision(&round, &field));
+
+    let mut square = debris(200.0, 114.5);
+    square.outline = vec![(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+    assert!(projectile.is_swept_collision(&square, &field));
+    square.set_position(200.0, 115.5);
+    assert!(!projectile.is_swept_collision(&square, &field));
+}
+
+#[test]
+fn fast_projectiles_hit_small_enemies() {
+    let mut game = Game::new_with_seed(1);
+    let _ = game.step(GameAction::Start);
+    let mut enemy = debris(200.0, 100.0);
+    enemy.health = 1;
+    game.enemies = vec![enemy];
+    game.projectiles = vec![fast_projectile((170.0, 100.0), 3000.0)];
+
+    let _ = game.step(GameAction::Tick);
+    assert!(game.enemies.iter().all(|e| e.id != 1000));
+    assert!(game.projectiles.is_empty());
+    assert!(game.coins > 0);
+}
+
+/// Creates a round enemy with the given radius, position and direction.
+fn asteroid(radius: f32, position: (f32, f32), direction: (f32, f32)) -> Enemy {
+    let mut enemy = enemy(radius, position, direction);
+    enemy.outline.clear();
+    enemy
+}
+
+#[test]
+fn elastic_bounce_swaps_speeds_of_equal_enemies() {
+    let mut a = asteroid(20.0, (100.0, 100.0), (2.0, 0.0));
+    let mut b = asteroid(20.0, (130.0, 100.0), (-2.0, 0.0));
+
+    a.bounce(&mut b, 1.0, &Field::new(1000, 1000));
+
+    assert_eq!(a.direction(), (-2.0, 0.0));
+    assert_eq!(b.direction(), (2.0, 0.0));
+    assert!(!a.is_collision(&b, &Field::new(1000, 1000)));
+}
+
+#[test]
+fn bounce_conserves_momentum() {
+    let mut small = asteroid(10.0, (100.0, 100.0), (3.0, 1.0));
+    let mut big = asteroid(30.0, (125.0, 105.0), (-1.0, 0.5));
+    let momentum = |a: &Enemy, b: &Enemy| {
+        let ((dx, dy), (dx2, dy2)) = (a.direction(), b.direction());
+        (
+            a.mass() * dx + b.mass() * dx2,
+            a.mass() * dy + b.mass() * dy2,
+        )
+    };
+    let before = momentum(&small, &big);
+
+    small.bounce(&mut big, 0.5, &Field::new(1000, 1000));
+
+    let after = momentum(&small, &big);
+    assert!((before.0 - after.0).abs() < 1e-2);
+    assert!((before.1 - after.1).abs() < 1e-2);
+    // The light enemy is thrown back, the heavy one barely slows down
+    assert!(small.direction().0 < 0.0);
+    assert!(big.direction().0 < 0.0);
+}
+
+#[test]
+fn enemies_pass_through_each_other_if_collisions_are_disabled() {
+    let mut game = Game::new_with_seed(1);
+    let _ = game.step(GameAction::Start);
+    game.game_settings.enemy_collisions = false;
+    let a = asteroid(20.0, (500.0, 300.0), (2.0, 0.0));
+    let b = asteroid(20.0, (520.0, 300.0), (-2.0, 0.0));
+    game.enemies = vec![a, b];
+
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.enemies[0].direction(), (2.0, 0.0));
+
+    game.game_settings.enemy_collisions = true;
+    let _ = game.step(GameAction::Tick);
+    assert!(game.enemies[0].direction().0 < 0.0);
+    assert!(game.enemies[1].direction().0 > 0.0);
+}
@@ -0,0 +1,51 @@
+//! Helpers shared by the integration tests. Every test crate only uses some of them.
+#![allow(dead_code)]
+
+use rand::SeedableRng;
+use wasm_asteroids::{
+    enemy::Enemy,
+    game::{Game, GameAction, GameRng},
+    game_settings::GameSettings,
+    positioned::{Moves, Positioned},
+    projectile::{Faction, Projectile},
+};
+
+/// Creates a projectile of the player at the given position that flies with the given velocity. It is used up by its first hit.
+pub fn projectile((x, y): (f32, f32), (dx, dy): (f32, f32)) -> Projectile {
+    Projectile {
+        id: 2000,
+        dx,
+        dy,
+        x,
+        y,
+        hits: 1,
+        damage: 1,
+        radius: 20.0,
+        lifetime: 2.0,
+        faction: Faction::Player,
+    }
+}
+
+/// Creates an enemy of a new game with the given radius at the given position that flies with the given velocity.
+pub fn enemy(radius: f32, (x, y): (f32, f32), (dx, dy): (f32, f32)) -> Enemy {
+    let mut enemy = Enemy::new(
+        &GameSettings::new(),
+        0.0,
+        1000,
+        &mut GameRng::seed_from_u64(0),
+    );
+    enemy.radius = radius;
+    enemy.set_position(x, y);
+    enemy.set_direction(dx, dy);
+    enemy
+}
+
+/// Creates a running game that doesn't spawn enemies or gravity wells on its own. The events of starting the game are already taken.
+pub fn quiet_game(seed: u64) -> Game {
+    let mut game = Game::new_with_seed(seed);
+    let _ = game.step(GameAction::Start);
+    game.time_until_enemy_spawn = f32::MAX;
+    game.game_settings.gravity_well_interval = 0.0;
+    let _ = game.take_events();
+    game
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::{enemy, projectile, quiet_game};
+use wasm_asteroids::{
+    enemy::Enemy,
+    game::GameAction,
+    game_settings::GameSettings,
+    projectile::{Faction, Projectile},
+};
+
+/// Creates an enemy ship that is ready to shoot.
+fn enemy_ship(x: f32, y: f32) -> Enemy {
+    let mut enemy = enemy(20.0, (x, y), (50.0, 0.0));
+    enemy.enemy_type = "Ship".to_string();
+    enemy.health = 2;
+    enemy.max_health = 2;
+    enemy.time_until_next_shot = 0.0;
+    enemy
+}
+
+fn hostile_projectile(x: f32, y: f32) -> Projectile {
+    Projectile {
+        radius: 12.0,
+        faction: Faction::Hostile,
+        ..projectile((x, y), (0.0, 0.0))
+    }
+}
+
+#[test]
+fn enemy_ships_shoot_at_the_ship() {
+    let mut game = quiet_game(12);
+    game.enemies = vec![enemy_ship(150.0, 350.0)];
+
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.projectiles.len(), 1);
+    let shot = game.projectiles[0];
+    assert_eq!(shot.faction, Faction::Hostile);
+    assert!(shot.dx > 0.0);
+    assert!(shot.dy.abs() < 1e-3);
+
+    // The enemy has to wait before it can shoot again
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.projectiles.len(), 1);
+}
+
+#[test]
+fn fleeing_ships_hold_their_fire() {
+    let mut game = quiet_game(12);
+    let mut enemy = enemy_ship(150.0, 350.0);
+    enemy.health = 1;
+    game.enemies = vec![enemy];
+
+    let _ = game.step(GameAction::Tick);
+    assert!(game.projectiles.is_empty());
+}
+
+#[test]
+fn hostile_projectiles_hit_the_ship() {
+    let mut game = quiet_game(12);
+    let ship = game.game_settings.ship;
+    game.projectiles = vec![hostile_projectile(ship.x, ship.y)];
+
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.lives, 2);
+    assert!(game.projectiles.is_empty());
+
+    // An invulnerable ship loses no life, but the projectile is still destroyed
+    game.projectiles = vec![hostile_projectile(ship.x, ship.y)];
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.lives, 2);
+    assert!(game.projectiles.is_empty());
+}
+
+#[test]
+fn hostile_projectiles_pass_through_enemies() {
+    let mut game = quiet_game(12);
+    let mut enemy = enemy_ship(150.0, 350.0);
+    enemy.time_until_next_shot = f32::MAX;
+    game.enemies = vec![enemy];
+    game.projectiles = vec![hostile_projectile(150.0, 350.0)];
+
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.enemies.len(), 1);
+    assert_eq!(game.enemies[0].health, 2);
+    assert_eq!(game.coins, 0);
+}
+
+#[test]
+fn enemy_ships_shoot_more_often_with_the_score() {
+    let settings = GameSettings::new();
+    assert_eq!(settings.enemy_shoot_timeout(0.0), 3.0);
+    assert_eq!(settings.enemy_shoot_timeout(500.0), 1.5);
+    assert!(settings.enemy_shoot_timeout(5000.0) < 0.5);
+}
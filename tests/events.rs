@@ -0,0 +1,94 @@
+mod common;
+
+use common::{enemy, projectile, quiet_game};
+use wasm_asteroids::{enemy::Enemy, game::GameAction, game_event::GameEvent};
+
+/// Creates a resting enemy with the given radius and position that is destroyed by a single hit.
+fn fragile_enemy(radius: f32, x: f32, y: f32) -> Enemy {
+    let mut enemy = enemy(radius, (x, y), (0.0, 0.0));
+    enemy.health = 1;
+    enemy
+}
+
+#[test]
+fn hitting_a_large_enemy_splits_it() {
+    let mut game = quiet_game(2);
+    game.enemies = vec![fragile_enemy(30.0, 300.0, 300.0)];
+    game.projectiles = vec![projectile((300.0, 300.0), (0.0, 0.0))];
+
+    let _ = game.step(GameAction::Tick);
+    let events = game.take_events();
+
+    let children = events
+        .iter()
+        .find_map(|event| match event {
+            GameEvent::EnemySplit {
+                id: 1000, children, ..
+            } => Some(children.clone()),
+            _ => None,
+        })
+        .unwrap();
+    let ids: Vec<i64> = game.enemies.iter().map(|e| e.id).collect();
+    assert_eq!(ids, children);
+    assert!(events.contains(&GameEvent::CoinsGained { amount: game.coins }));
+}
+
+#[test]
+fn small_enemies_are_damaged_and_destroyed() {
+    let mut game = quiet_game(2);
+    let mut small = fragile_enemy(5.0, 300.0, 300.0);
+    small.health = 2;
+    game.enemies = vec![small];
+
+    for _ in 0..2 {
+        game.projectiles = vec![projectile((300.0, 300.0), (0.0, 0.0))];
+        let _ = game.step(GameAction::Tick);
+    }
+    let events = game.take_events();
+    assert!(events.iter().any(|event| matches!(
+        event,
+        GameEvent::EnemyDamaged {
+            id: 1000,
+            health: 1,
+            ..
+        }
+    )));
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, GameEvent::EnemyDestroyed { id: 1000, .. })));
+}
+
+#[test]
+fn losing_the_last_life_ends_the_game() {
+    let mut game = quiet_game(2);
+    game.lives = 1;
+    let ship = game.game_settings.ship;
+    game.enemies = vec![fragile_enemy(20.0, ship.x, ship.y)];
+
+    let _ = game.step(GameAction::Tick);
+    let events = game.take_events();
+    assert!(events.contains(&GameEvent::ShipHit { lives: 0 }));
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, GameEvent::EnemyDestroyed { id: 1000, .. })));
+    assert!(matches!(events.last(), Some(GameEvent::GameOver { .. })));
+}
+
+#[test]
+fn events_are_taken_once() {
+    let mut game = quiet_game(2);
+    game.coins = 10_000;
+    let _ = game.step(GameAction::BuyItem(0, false));
+    let _ = game.step(GameAction::Shoot);
+
+    let events = game.take_events();
+    assert_eq!(
+        events[0],
+        GameEvent::ItemBought {
+            item_id: 0,
+            level: 1
+        }
+    );
+    assert!(matches!(events[1], GameEvent::ProjectileFired { .. }));
+    assert!(game.take_events().is_empty());
+}
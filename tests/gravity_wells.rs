@@ -0,0 +1,105 @@
+mod common;
+
+use common::{enemy, projectile, quiet_game};
+use rand::SeedableRng;
+use wasm_asteroids::{
+    game::{Game, GameAction, GameRng},
+    game_settings::{field::Field, GameSettings},
+    gravity_well::GravityWell,
+    positioned::Positioned,
+};
+
+fn well(x: f32, y: f32) -> GravityWell {
+    GravityWell {
+        id: 3000,
+        x,
+        y,
+        strength: 1_000_000.0,
+        horizon: 20.0,
+    }
+}
+
+/// Creates a running game whose only gravity well is at the given position.
+fn game_with_well(x: f32, y: f32) -> Game {
+    let mut game = quiet_game(4);
+    game.gravity_wells = vec![well(x, y)];
+    game
+}
+
+#[test]
+fn wells_bend_projectiles_towards_them() {
+    let field = Field::new(1100, 700);
+    let well = well(550.0, 200.0);
+    let mut shot = projectile((400.0, 300.0), (250.0, 0.0));
+
+    well.pull(&mut shot, &field);
+    assert!(shot.dx > 250.0);
+    assert!(shot.dy < 0.0);
+
+    // The pull gets weaker with the square of the distance
+    let mut far_shot = projectile((150.0, 600.0), (250.0, 0.0));
+    well.pull(&mut far_shot, &field);
+    assert!(far_shot.dy.abs() < shot.dy.abs() / 5.0);
+}
+
+#[test]
+fn wells_swallow_enemies_and_projectiles() {
+    let mut game = game_with_well(800.0, 350.0);
+    game.enemies = vec![enemy(10.0, (830.0, 350.0), (-1000.0, 0.0))];
+    game.projectiles = vec![projectile((770.0, 350.0), (1000.0, 0.0))];
+
+    let _ = game.step(GameAction::Tick);
+    assert!(game.enemies.is_empty());
+    assert!(game.projectiles.is_empty());
+    assert_eq!(game.coins, 0);
+}
+
+#[test]
+fn ships_flying_into_a_well_lose_a_life() {
+    let mut game = game_with_well(560.0, 350.0);
+
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.lives, 2);
+    assert!(game.is_invulnerable());
+}
+
+#[test]
+fn wells_appear_with_the_score() {
+    let mut game = Game::new_with_seed(6);
+    let _ = game.step(GameAction::Start);
+    game.score = 650.0;
+    for _ in 0..5 {
+        let _ = game.step(GameAction::Tick);
+    }
+    assert_eq!(game.gravity_wells.len(), 2);
+
+    let ship = game.game_settings.ship;
+    for well in &game.gravity_wells {
+        let distance = ((well.x - ship.x).powi(2) + (well.y - ship.y).powi(2)).sqrt();
+        assert!(distance >= 250.0);
+    }
+
+    game.score = 100_000.0;
+    for _ in 0..5 {
+        let _ = game.step(GameAction::Tick);
+    }
+    assert_eq!(
+        game.gravity_wells.len(),
+        game.game_settings.max_gravity_wells as usize
+    );
+}
+
+#[test]
+fn wells_are_skipped_when_there_is_no_room_for_them() {
+    let mut rng = GameRng::seed_from_u64(6);
+    let mut settings = GameSettings::new();
+    // Narrower than the margin to the edges on both sides and close to the ship everywhere
+    settings.field = Field::new(150, 120);
+    settings.ship.set_position(75.0, 60.0);
+    assert!(GravityWell::new(&settings, 1, &mut rng).is_none());
+
+    settings.field = Field::new(1500, 120);
+    let well = GravityWell::new(&settings, 1, &mut rng).unwrap();
+    assert!(well.x >= 100.0 && well.x <= 1400.0);
+    assert_eq!(well.y, 60.0);
+}
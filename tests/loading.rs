@@ -0,0 +1,132 @@
+use serde_json::{json, Value};
+use wasm_asteroids::{
+    game::{Game, GameAction, GameState},
+    game_ressources::{GameRessource, LoadError},
+    snapshot::GameSnapshot,
+    GameModel,
+};
+
+fn save(items: Value) -> Value {
+    json!({
+        "version": 2,
+        "id": 1,
+        "ended": false,
+        "score": 50.0,
+        "coins": 10,
+        "lives": 3,
+        "enemy_spawn_timeout": 75.0,
+        "items": items
+    })
+}
+
+fn item(id: u32, level: u32) -> Value {
+    json!({ "level": level, "item": { "id": id, "name": "", "description": "", "price": 0 } })
+}
+
+fn load(save: Value) -> Result<Game, LoadError> {
+    Game::load(GameRessource::from_json(save)?)
+}
+
+#[test]
+fn loads_valid_save() {
+    let game = load(save(json!([item(0, 2), item(3, 1)]))).unwrap();
+
+    assert_eq!(game.state, GameState::Paused);
+    assert_eq!(game.coins, 10);
+    assert_eq!(game.shop.items[0].level, 2);
+    assert_eq!(game.game_settings.coin_multiplier, 11);
+}
+
+#[test]
+fn rejects_unknown_items() {
+    assert_eq!(
+        load(save(json!([item(42, 1)]))).err(),
+        Some(LoadError::UnknownItem(42))
+    );
+}
+
+#[test]
+fn rejects_levels_above_max_level() {
+    assert_eq!(
+        load(save(json!([item(4, 3)]))).err(),
+        Some(LoadError::LevelTooHigh(4))
+    );
+    assert_eq!(
+        load(save(json!([item(4, 2), item(4, 1)]))).err(),
+        Some(LoadError::LevelTooHigh(4))
+    );
+}
+
+#[test]
+fn rejects_invalid_resources() {
+    let mut negative_coins = save(json!([]));
+    negative_coins["coins"] = json!(-1);
+    assert_eq!(load(negative_coins).err(), Some(LoadError::NegativeCoins));
+
+    let mut too_many_lives = save(json!([]));
+    too_many_lives["lives"] = json!(4);
+    assert_eq!(load(too_many_lives).err(), Some(LoadError::InvalidLives));
+}
+
+#[test]
+fn validates_snapshots() {
+    let mut game = Game::new_with_seed(1);
+    let _ = game.step(GameAction::Start);
+    let mut snapshot = game.snapshot();
+    snapshot.item_levels[1].1 = 9;
+
+    let mut tampered = save(json!([]));
+    tampered["snapshot"] = json!(snapshot.to_json());
+    assert_eq!(load(tampered).err(), Some(LoadError::LevelTooHigh(1)));
+
+    let mut corrupted = save(json!([]));
+    corrupted["snapshot"] = json!("{");
+    assert_eq!(load(corrupted).err(), Some(LoadError::Malformed));
+}
+
+#[test]
+fn validates_snapshot_settings_against_their_items() {
+    let mut game = Game::new_with_seed(1);
+    let _ = game.step(GameAction::Start);
+    game.coins = 10_000;
+    game.step(GameAction::BuyItem(0, false)).unwrap();
+    game.step(GameAction::BuyItem(3, false)).unwrap();
+    let snapshot = game.snapshot();
+    assert_eq!(snapshot.validate(), Ok(()));
+
+    let mut more_lives = snapshot.clone();
+    more_lives.game_settings.max_lives = 10;
+    more_lives.lives = 10;
+    assert_eq!(more_lives.validate(), Err(LoadError::InvalidLives));
+
+    let mut richer = snapshot.clone();
+    richer.game_settings.coin_multiplier += 1;
+    assert_eq!(richer.validate(), Err(LoadError::InvalidSettings));
+
+    let mut faster = snapshot;
+    faster.game_settings.shoot_timeout = 0.0;
+    assert_eq!(faster.validate(), Err(LoadError::InvalidSettings));
+}
+
+#[test]
+fn models_keep_their_game_when_a_snapshot_is_invalid() {
+    let mut model = GameModel::new_with_seed(1);
+    model.start_game_with_seed(1);
+    let mut snapshot = GameSnapshot::from_json(&model.get_snapshot()).unwrap();
+    snapshot.lives = 100;
+    snapshot.game_settings.max_lives = 100;
+
+    assert!(!model.load_snapshot(&snapshot.to_json()));
+    assert_eq!(model.get_lives(), 3);
+    assert_eq!(
+        model.get_error_message().as_deref(),
+        Some("The saved game has an invalid number of lives")
+    );
+
+    assert!(!model.load_snapshot("{"));
+    assert_eq!(
+        model.get_error_message().as_deref(),
+        Some("Invalid snapshot")
+    );
+    assert!(model.load_snapshot(&model.get_snapshot()));
+}
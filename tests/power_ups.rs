@@ -0,0 +1,187 @@
+mod common;
+
+use common::{enemy, projectile, quiet_game};
+use wasm_asteroids::{
+    game::{Game, GameAction, TICKS_PER_SECOND},
+    game_event::GameEvent,
+    positioned::Positioned,
+    power_up::{PowerUp, PowerUpKind},
+};
+
+/// Creates a resting power-up of the given kind far away from the ship.
+fn power_up(kind: PowerUpKind) -> PowerUp {
+    PowerUp {
+        id: 3000,
+        x: 100.0,
+        y: 100.0,
+        dx: 0.0,
+        dy: 0.0,
+        radius: 12.0,
+        kind,
+        lifetime: 8.0,
+    }
+}
+
+/// Lets the ship collect a power-up of the given kind.
+fn collect(game: &mut Game, kind: PowerUpKind) {
+    let mut power_up = power_up(kind);
+    let (x, y) = game.game_settings.ship.get_position();
+    power_up.set_position(x, y);
+    game.power_ups = vec![power_up];
+    let _ = game.step(GameAction::Tick);
+}
+
+/// Lets the game run until the effects of all collected power-ups ran out.
+fn wait_for_effects(game: &mut Game) {
+    let ticks = game.game_settings.power_up_duration * TICKS_PER_SECOND as f32;
+    for _ in 0..ticks as usize + 1 {
+        let _ = game.step(GameAction::Tick);
+    }
+}
+
+#[test]
+fn destroyed_enemies_drop_power_ups() {
+    let mut game = quiet_game(24);
+    game.game_settings.power_up_probability = 1.0;
+    let mut enemy = enemy(5.0, (300.0, 300.0), (100.0, 0.0));
+    enemy.health = 1;
+    game.enemies = vec![enemy];
+    game.projectiles = vec![projectile((300.0, 300.0), (0.0, 0.0))];
+
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.power_ups.len(), 1);
+    // The power-up keeps drifting in the direction of the enemy
+    assert!(game.power_ups[0].dx > 0.0);
+    assert!(game
+        .take_events()
+        .iter()
+        .any(|event| matches!(event, GameEvent::PowerUpDropped { .. })));
+}
+
+#[test]
+fn power_ups_can_be_shot() {
+    let mut game = quiet_game(24);
+    game.power_ups = vec![power_up(PowerUpKind::CoinBurst)];
+    game.projectiles = vec![projectile((100.0, 100.0), (0.0, 0.0))];
+
+    let _ = game.step(GameAction::Tick);
+    assert!(game.power_ups.is_empty());
+    assert!(game.projectiles.is_empty());
+    let coins = game.game_settings.power_up_coins * game.game_settings.coin_multiplier;
+    assert_eq!(game.coins, coins);
+    assert!(game.take_events().contains(&GameEvent::PowerUpCollected {
+        id: 3000,
+        kind: PowerUpKind::CoinBurst
+    }));
+}
+
+#[test]
+fn uncollected_power_ups_disappear() {
+    let mut game = quiet_game(24);
+    game.power_ups = vec![power_up(PowerUpKind::Shield)];
+    let ticks = game.game_settings.power_up_lifetime * TICKS_PER_SECOND as f32;
+    for _ in 0..ticks as usize + 1 {
+        let _ = game.step(GameAction::Tick);
+    }
+    assert!(game.power_ups.is_empty());
+    assert!(!game.game_settings.ship_shielded);
+}
+
+#[test]
+fn rapid_fire_is_reverted_without_losing_bought_items() {
+    let mut game = quiet_game(24);
+    let timeout = game.game_settings.shoot_timeout;
+    collect(&mut game, PowerUpKind::RapidFire);
+    assert_eq!(game.game_settings.shoot_timeout, timeout);
+    game.step(GameAction::Shoot).unwrap();
+    assert_eq!(game.time_until_next_shot, timeout / 2.0);
+
+    // Buying an item during the effect keeps its bonus after the effect
+    game.coins = 10_000;
+    game.step(GameAction::BuyItem(0, false)).unwrap();
+    wait_for_effects(&mut game);
+    assert!((game.game_settings.shoot_timeout - (timeout - 0.2)).abs() < 1e-5);
+    assert!(game.effects.is_empty());
+    assert!(game.take_events().contains(&GameEvent::PowerUpExpired {
+        kind: PowerUpKind::RapidFire
+    }));
+}
+
+#[test]
+fn buying_during_rapid_fire_keeps_the_shoot_timeout_positive() {
+    let mut game = quiet_game(24);
+    collect(&mut game, PowerUpKind::RapidFire);
+    game.coins = 100_000;
+    for _ in 0..6 {
+        game.step(GameAction::BuyItem(0, false)).unwrap();
+    }
+    let timeout = game.game_settings.shoot_timeout;
+    assert!(timeout > 0.0);
+
+    // Rapid fire still halves the fully upgraded timeout
+    game.step(GameAction::Shoot).unwrap();
+    assert!((game.time_until_next_shot - timeout / 2.0).abs() < 1e-5);
+    wait_for_effects(&mut game);
+    assert_eq!(game.game_settings.shoot_timeout, timeout);
+}
+
+#[test]
+fn triple_shot_fires_a_fan() {
+    let mut game = quiet_game(24);
+    collect(&mut game, PowerUpKind::TripleShot);
+    let _ = game.step(GameAction::Shoot);
+    assert_eq!(game.projectiles.len(), 3);
+    let directions: Vec<f32> = game.projectiles.iter().map(|p| p.dy.atan2(p.dx)).collect();
+    assert!(directions[0] != directions[1] && directions[1] != directions[2]);
+
+    // Collecting it again only restarts the effect
+    collect(&mut game, PowerUpKind::TripleShot);
+    assert_eq!(game.game_settings.volley_size, 3);
+    wait_for_effects(&mut game);
+    assert_eq!(game.game_settings.volley_size, 1);
+}
+
+#[test]
+fn the_shield_protects_the_ship_until_it_runs_out() {
+    let mut game = quiet_game(24);
+    collect(&mut game, PowerUpKind::Shield);
+    let lives = game.lives;
+    let ship = game.game_settings.ship;
+    game.enemies = vec![enemy(20.0, (ship.x, ship.y), (0.0, 0.0))];
+
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.lives, lives);
+    wait_for_effects(&mut game);
+    assert!(!game.is_invulnerable());
+}
+
+#[test]
+fn extra_lives_are_capped() {
+    let mut game = quiet_game(24);
+    game.lives = 1;
+    collect(&mut game, PowerUpKind::ExtraLife);
+    assert_eq!(game.lives, 2);
+    for _ in 0..2 {
+        collect(&mut game, PowerUpKind::ExtraLife);
+    }
+    assert_eq!(game.lives, game.game_settings.max_lives);
+}
+
+#[test]
+fn power_ups_and_effects_survive_a_save() {
+    let mut game = quiet_game(24);
+    collect(&mut game, PowerUpKind::RapidFire);
+    game.coins = 10_000;
+    game.step(GameAction::BuyItem(0, false)).unwrap();
+    game.power_ups = vec![power_up(PowerUpKind::Shield)];
+
+    // The settings changed by the effect still match the items of the save
+    assert!(game.snapshot().validate().is_ok());
+    let restored = Game::from_snapshot(game.snapshot());
+    assert_eq!(restored.power_ups, game.power_ups);
+    assert_eq!(restored.effects, game.effects);
+    assert_eq!(
+        restored.game_settings.shoot_timeout,
+        game.game_settings.shoot_timeout
+    );
+}
@@ -0,0 +1,86 @@
+use wasm_asteroids::game::{Game, GameAction, GameState};
+
+fn running_game(depth: usize) -> Game {
+    let mut game = Game::new_with_seed(5);
+    game.set_history_depth(depth);
+    let _ = game.step(GameAction::Start);
+    let _ = game.step(GameAction::RotateShip(1.0, 0.5));
+    game
+}
+
+fn tick(game: &mut Game, ticks: usize) {
+    for _ in 0..ticks {
+        let _ = game.step(GameAction::Shoot);
+        let _ = game.step(GameAction::Tick);
+    }
+}
+
+#[test]
+fn rewind_and_step_forward_restore_the_frames() {
+    let mut game = running_game(100);
+    tick(&mut game, 200);
+    let before = game.snapshot().to_json();
+    let score = game.score;
+
+    assert_eq!(game.rewind(30), 30);
+    assert_eq!(game.state, GameState::Paused);
+    assert!(game.score < score);
+
+    for _ in 0..30 {
+        assert!(game.step_forward());
+    }
+    assert!(!game.step_forward());
+
+    let mut after = game.snapshot();
+    after.state = GameState::Running;
+    assert_eq!(before, after.to_json());
+}
+
+#[test]
+fn history_is_bounded_by_depth() {
+    let mut game = running_game(10);
+    tick(&mut game, 50);
+
+    assert_eq!(game.rewind(50), 10);
+}
+
+#[test]
+fn new_frames_drop_the_rewound_frames() {
+    let mut game = running_game(100);
+    tick(&mut game, 50);
+    game.rewind(10);
+
+    let _ = game.step(GameAction::Start);
+    tick(&mut game, 1);
+    assert!(!game.step_forward());
+}
+
+#[test]
+fn actions_after_a_rewind_drop_the_rewound_frames() {
+    let mut game = running_game(100);
+    tick(&mut game, 50);
+    game.rewind(10);
+    game.coins = 10_000;
+    game.step(GameAction::BuyItem(0, false)).unwrap();
+    assert!(!game.step_forward());
+
+    game.rewind(10);
+    let _ = game.step(GameAction::Start);
+    let _ = game.step(GameAction::RotateShip(0.0, 1.0));
+    assert!(!game.step_forward());
+
+    // Only pausing and resuming keeps them
+    game.rewind(10);
+    let _ = game.step(GameAction::Start);
+    let _ = game.step(GameAction::Pause);
+    assert!(game.step_forward());
+}
+
+#[test]
+fn rewinding_is_disabled_by_default() {
+    let mut game = running_game(0);
+    tick(&mut game, 50);
+
+    assert_eq!(game.rewind(10), 0);
+    assert_eq!(game.state, GameState::Running);
+}
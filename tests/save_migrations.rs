@@ -0,0 +1,133 @@
+use serde_json::{json, Value};
+use wasm_asteroids::{
+    game::{Game, GameAction},
+    game_ressources::{GameRessource, LoadError, SAVE_VERSION},
+};
+
+/// A save as it was written before saves were versioned.
+fn v1_save() -> Value {
+    json!({
+        "id": 4,
+        "ended": false,
+        "score": 120.5,
+        "coins": 30,
+        "lives": 2,
+        "enemy_spawn_timeout": 70.0,
+        "items": [
+            {
+                "level": 2,
+                "item": { "id": 0, "name": "Energy Refresher", "description": "Increases the fire rate", "price": 100 }
+            }
+        ]
+    })
+}
+
+/// A save as it was written by version 2, which measured timers in ticks and speeds in pixels per tick.
+fn v2_save() -> Value {
+    let mut game = Game::new_with_seed(3);
+    let _ = game.step(GameAction::Start);
+    let _ = game.step(GameAction::Tick);
+
+    let mut snapshot: Value = serde_json::from_str(&game.snapshot().to_json()).unwrap();
+    snapshot["time_until_enemy_spawn"] = json!(80);
+    snapshot["game_settings"]["projectile_speed"] = json!(5.0);
+    snapshot["game_settings"]["shoot_timeout"] = json!(70);
+    snapshot["game_settings"]["invulnerability_time"] = json!(100);
+    snapshot["game_settings"]["ship_movement"] =
+        json!({ "thrust": 0.15, "drag": 0.01, "max_speed": 6.0 });
+    snapshot["enemies"][0]["dx"] = json!(2.0);
+
+    let mut save = v1_save();
+    save["version"] = json!(2);
+    save["snapshot"] = json!(snapshot.to_string());
+    save
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-3,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn migrates_v1_to_current() {
+    let save = GameRessource::from_json(v1_save()).unwrap();
+
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_close(save.enemy_spawn_timeout, 1.4);
+    assert_eq!(save.id, Some(4));
+    assert_eq!(save.score, 120.5);
+    assert_eq!(save.items[0].level, 2);
+    assert_eq!(save.items[0].item.id, 0);
+    assert!(save.snapshot.is_none());
+}
+
+#[test]
+fn migrates_v2_timers_and_speeds_to_seconds() {
+    let game = Game::load(GameRessource::from_json(v2_save()).unwrap()).unwrap();
+    let settings = &game.game_settings;
+
+    assert_close(game.time_until_enemy_spawn, 1.6);
+    assert_close(settings.projectile_speed, 250.0);
+    assert_close(settings.shoot_timeout, 1.4);
+    assert_close(settings.invulnerability_time, 2.0);
+    assert_close(game.enemies[0].dx, 100.0);
+
+    let movement = settings.ship_movement.unwrap();
+    assert_close(movement.thrust, 375.0);
+    assert_close(movement.max_speed, 300.0);
+    assert_close(movement.drag, 0.395);
+}
+
+#[test]
+fn loads_current_version_unchanged() {
+    let mut save = v1_save();
+    save["version"] = json!(SAVE_VERSION);
+    save["snapshot"] = json!("{}");
+
+    let save = GameRessource::from_json(save).unwrap();
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.snapshot.as_deref(), Some("{}"));
+}
+
+#[test]
+fn rejects_unknown_versions() {
+    let mut save = v1_save();
+    save["version"] = json!(SAVE_VERSION + 1);
+    assert_eq!(
+        GameRessource::from_json(save).err(),
+        Some(LoadError::UnknownVersion(SAVE_VERSION + 1))
+    );
+
+    let mut save = v1_save();
+    save["version"] = json!(0);
+    assert_eq!(
+        GameRessource::from_json(save).err(),
+        Some(LoadError::UnknownVersion(0))
+    );
+}
+
+#[test]
+fn rejects_malformed_saves() {
+    let mut save = v1_save();
+    save["version"] = json!("two");
+    assert_eq!(
+        GameRessource::from_json(save).err(),
+        Some(LoadError::Malformed)
+    );
+
+    let mut save = v1_save();
+    save["items"] = json!(3);
+    assert_eq!(
+        GameRessource::from_json(save).err(),
+        Some(LoadError::Malformed)
+    );
+
+    assert_eq!(
+        GameRessource::from_json(json!([1, 2])).err(),
+        Some(LoadError::Malformed)
+    );
+}
@@ -0,0 +1,123 @@
+use rand::SeedableRng;
+use wasm_asteroids::{
+    enemy::Enemy,
+    game::{Game, GameAction, GameRng},
+    game_settings::{
+        field::FieldTopology,
+        ship::{Ship, ShipMovement},
+        GameSettings,
+    },
+    positioned::{Moves, Positioned},
+    replay::Replay,
+    wave::WaveGroup,
+};
+
+/// Creates a running game without enemies where the ship can move freely.
+fn game(topology: FieldTopology) -> Game {
+    let mut replay = Replay::new(3);
+    replay.topology = topology;
+    replay.ship_movement = true;
+    let mut game = replay.new_game();
+    let _ = game.step(GameAction::Start);
+    game.game_settings.enemy_spawn_timeout = 1e6;
+    game.time_until_enemy_spawn = f32::MAX;
+    game
+}
+
+fn ticks(game: &mut Game, ticks: usize) {
+    for _ in 0..ticks {
+        let _ = game.step(GameAction::Tick);
+    }
+}
+
+#[test]
+fn ship_is_pinned_by_default() {
+    let mut game = Game::new_with_seed(3);
+    let _ = game.step(GameAction::Start);
+    let position = game.game_settings.ship.get_position();
+
+    let _ = game.step(GameAction::RotateShip(1.0, 0.0));
+    let _ = game.step(GameAction::Thrust(true));
+    ticks(&mut game, 10);
+    assert_eq!(game.game_settings.ship.get_position(), position);
+}
+
+#[test]
+fn thrust_accelerates_in_the_looking_direction() {
+    let mut game = game(FieldTopology::Bounded);
+    let (x, y) = game.game_settings.ship.get_position();
+
+    // Looking down the screen means flying down the field
+    let _ = game.step(GameAction::RotateShip(0.0, 1.0));
+    let _ = game.step(GameAction::Thrust(true));
+    ticks(&mut game, 10);
+    let ship = game.game_settings.ship;
+    assert_eq!(ship.x, x);
+    assert!(ship.y < y);
+    assert!(ship.vy < 0.0);
+
+    // The ship keeps drifting after the engine is stopped, but slows down
+    let _ = game.step(GameAction::Thrust(false));
+    ticks(&mut game, 10);
+    assert!(game.game_settings.ship.y < ship.y);
+    assert!(game.game_settings.ship.vy > ship.vy);
+}
+
+#[test]
+fn speed_is_limited() {
+    let movement = ShipMovement::new();
+    let mut game = game(FieldTopology::Toroidal);
+    let _ = game.step(GameAction::RotateShip(1.0, 1.0));
+    let _ = game.step(GameAction::Thrust(true));
+    ticks(&mut game, 500);
+
+    let ship = game.game_settings.ship;
+    let speed = (ship.vx.powi(2) + ship.vy.powi(2)).sqrt();
+    assert!((speed - movement.max_speed).abs() < 1e-3);
+}
+
+#[test]
+fn ship_stops_at_the_edge_of_a_bounded_field() {
+    let mut game = game(FieldTopology::Bounded);
+    let _ = game.step(GameAction::RotateShip(1.0, 0.0));
+    let _ = game.step(GameAction::Thrust(true));
+    ticks(&mut game, 300);
+
+    let ship = game.game_settings.ship;
+    assert_eq!(ship.x, game.game_settings.field.width as f32);
+    assert_eq!(ship.vx, 0.0);
+}
+
+#[test]
+fn ship_wraps_around_a_toroidal_field() {
+    let mut game = game(FieldTopology::Toroidal);
+    let _ = game.step(GameAction::RotateShip(-1.0, 0.0));
+    let _ = game.step(GameAction::Thrust(true));
+    let mut wrapped = false;
+    for _ in 0..300 {
+        let before = game.game_settings.ship.x;
+        let _ = game.step(GameAction::Tick);
+        wrapped |= game.game_settings.ship.x > before;
+    }
+    assert!(wrapped);
+}
+
+#[test]
+fn enemy_ships_aim_at_the_ship() {
+    let mut settings = GameSettings::new();
+    // Every enemy is a ship that flies towards the player
+    let ships = WaveGroup::new(Some("Ship"), 20);
+    settings.ship_visibility = 10.0;
+    settings.ship = Ship::new(&settings.field);
+    settings.ship.set_position(100.0, 600.0);
+    let mut rng = GameRng::seed_from_u64(8);
+
+    for id in 0..20 {
+        let enemy = Enemy::from_group(&settings, &ships, 1000.0, id, &mut rng);
+        let (dx, dy) = enemy.direction();
+        let (to_x, to_y) = (100.0 - enemy.x, 600.0 - enemy.y);
+        let cos = (dx * to_x + dy * to_y)
+            / ((dx * dx + dy * dy).sqrt() * (to_x * to_x + to_y * to_y).sqrt());
+        assert!(cos > 0.999);
+    }
+}
@@ -0,0 +1,71 @@
+use wasm_asteroids::{
+    game::{Game, GameAction},
+    replay::Replay,
+};
+
+/// Plays a game with the given seed where the ship shoots in a circle.
+fn play(seed: u64, ticks: u32) -> (Game, Replay) {
+    let mut game = Game::new_with_seed(seed);
+    let mut replay = Replay::new(seed);
+    let mut take = |game: &mut Game, action: GameAction| {
+        replay.record(action);
+        let _ = game.step(action);
+    };
+
+    take(&mut game, GameAction::Start);
+    for tick in 0..ticks {
+        let angle = tick as f32 / 20.0;
+        take(&mut game, GameAction::RotateShip(angle.cos(), angle.sin()));
+        take(&mut game, GameAction::Shoot);
+        if tick % 500 == 0 {
+            take(&mut game, GameAction::BuyItem(0, false));
+        }
+        take(&mut game, GameAction::Tick);
+    }
+    (game, replay)
+}
+
+fn enemy_positions(game: &Game) -> Vec<(i64, f32, f32)> {
+    game.enemies.iter().map(|e| (e.id, e.x, e.y)).collect()
+}
+
+#[test]
+fn same_seed_plays_out_the_same() {
+    let (a, _) = play(42, 2000);
+    let (b, _) = play(42, 2000);
+
+    assert_eq!(a.score, b.score);
+    assert_eq!(a.coins, b.coins);
+    assert_eq!(a.lives, b.lives);
+    assert_eq!(enemy_positions(&a), enemy_positions(&b));
+}
+
+#[test]
+fn different_seeds_play_out_differently() {
+    let (a, _) = play(1, 500);
+    let (b, _) = play(2, 500);
+
+    assert_ne!(enemy_positions(&a), enemy_positions(&b));
+}
+
+#[test]
+fn replay_reproduces_the_run() {
+    let (game, replay) = play(7, 3000);
+    let replay = Replay::from_json(&replay.to_json()).unwrap();
+    let replayed = replay.play().unwrap();
+
+    assert_eq!(game.score, replayed.score);
+    assert_eq!(game.coins, replayed.coins);
+    assert_eq!(game.lives, replayed.lives);
+    assert_eq!(game.state, replayed.state);
+    assert_eq!(enemy_positions(&game), enemy_positions(&replayed));
+    assert_eq!(
+        game.shop.items.iter().map(|i| i.level).collect::<Vec<_>>(),
+        replayed
+            .shop
+            .items
+            .iter()
+            .map(|i| i.level)
+            .collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,40 @@
+use wasm_asteroids::{
+    game::{Game, GameAction},
+    snapshot::GameSnapshot,
+};
+
+/// Lets the ship shoot in a circle for the given number of ticks.
+fn run(game: &mut Game, from: u32, ticks: u32) {
+    for tick in from..from + ticks {
+        let angle = tick as f32 / 15.0;
+        let _ = game.step(GameAction::RotateShip(angle.cos(), angle.sin()));
+        let _ = game.step(GameAction::Shoot);
+        let _ = game.step(GameAction::Tick);
+    }
+}
+
+#[test]
+fn snapshot_round_trips_exactly() {
+    let mut game = Game::new_with_seed(4);
+    let _ = game.step(GameAction::Start);
+    run(&mut game, 0, 1500);
+    assert!(!game.enemies.is_empty());
+
+    let json = game.snapshot().to_json();
+    let restored = Game::from_snapshot(GameSnapshot::from_json(&json).unwrap());
+    assert_eq!(json, restored.snapshot().to_json());
+}
+
+#[test]
+fn restored_game_continues_the_same_way() {
+    let mut game = Game::new_with_seed(11);
+    let _ = game.step(GameAction::Start);
+    run(&mut game, 0, 1000);
+
+    let json = game.snapshot().to_json();
+    let mut restored = Game::from_snapshot(GameSnapshot::from_json(&json).unwrap());
+
+    run(&mut game, 1000, 2000);
+    run(&mut restored, 1000, 2000);
+    assert_eq!(game.snapshot().to_json(), restored.snapshot().to_json());
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use rand::{Rng, SeedableRng};
+use wasm_asteroids::{
+    game::GameRng,
+    game_settings::field::{Field, FieldTopology},
+    positioned::Positioned,
+    projectile::Projectile,
+    spatial_hash::SpatialHash,
+};
+
+/// Creates a resting projectile with the given radius.
+fn projectile(x: f32, y: f32, radius: f32) -> Projectile {
+    Projectile {
+        radius,
+        ..common::projectile((x, y), (0.0, 0.0))
+    }
+}
+
+/// Checks that the grid finds the same collisions as checking every pair of objects.
+fn assert_query_finds_every_collision(field: Field, (min, max): (f32, f32)) {
+    let mut rng = GameRng::seed_from_u64(9);
+    let mut random = || {
+        projectile(
+            rng.gen_range(min..field.width as f32 + max),
+            rng.gen_range(min..field.height as f32 + max),
+            rng.gen_range(5.0..90.0),
+        )
+    };
+    let objects: Vec<Projectile> = (0..300).map(|_| random()).collect();
+    let others: Vec<Projectile> = (0..300).map(|_| random()).collect();
+
+    let grid = SpatialHash::from_objects(&field, &objects);
+    for other in &others {
+        let expected: Vec<usize> = (0..objects.len())
+            .filter(|&i| other.is_collision(&objects[i], &field))
+            .collect();
+        let found: Vec<usize> = grid
+            .query(other)
+            .into_iter()
+            .filter(|&i| other.is_collision(&objects[i], &field))
+            .collect();
+        assert_eq!(expected, found);
+    }
+}
+
+#[test]
+fn query_finds_every_collision() {
+    // Some objects are outside of the field, like freshly spawned enemies
+    assert_query_finds_every_collision(Field::new(1100, 700), (-100.0, 100.0));
+}
+
+#[test]
+fn query_finds_every_collision_across_edges() {
+    let mut field = Field::new(1050, 720);
+    field.topology = FieldTopology::Toroidal;
+    assert_query_finds_every_collision(field, (0.0, 0.0));
+}
+
+#[test]
+fn query_skips_far_away_objects() {
+    let field = Field::new(1100, 700);
+    let objects = vec![
+        projectile(50.0, 50.0, 20.0),
+        projectile(1000.0, 600.0, 20.0),
+    ];
+
+    let grid = SpatialHash::from_objects(&field, &objects);
+    assert_eq!(grid.query(&projectile(60.0, 40.0, 20.0)), vec![0]);
+}
@@ -0,0 +1,100 @@
+use rand::SeedableRng;
+use wasm_asteroids::{
+    enemy::Enemy,
+    game::{GameRng, TICK_DURATION},
+    game_settings::GameSettings,
+    positioned::{Moves, Positioned},
+    steering::{steer, Behaviour},
+};
+
+/// Creates an undamaged enemy ship at the given position flying with the given velocity.
+fn enemy_ship(settings: &GameSettings, (x, y): (f32, f32), (dx, dy): (f32, f32)) -> Enemy {
+    let mut enemy = Enemy::new(settings, 0.0, 1000, &mut GameRng::seed_from_u64(0));
+    enemy.enemy_type = "Ship".to_string();
+    enemy.health = 2;
+    enemy.max_health = 2;
+    enemy.set_position(x, y);
+    enemy.set_direction(dx, dy);
+    enemy
+}
+
+fn angle((dx, dy): (f32, f32)) -> f32 {
+    dy.atan2(dx)
+}
+
+#[test]
+fn ships_turn_towards_the_ship_at_a_limited_rate() {
+    let settings = GameSettings::new();
+    // The ship is in the middle of the field, straight to the right of the enemy
+    let mut enemy = enemy_ship(&settings, (150.0, 350.0), (0.0, 100.0));
+
+    steer(&mut enemy, &settings);
+    assert_eq!(enemy.behaviour, Behaviour::Seek);
+    let max_turn = settings.enemy_turn_rate * settings.ship_visibility / 10.0 * TICK_DURATION;
+    assert!((angle(enemy.direction()) - (angle((0.0, 100.0)) - max_turn)).abs() < 1e-4);
+    assert!(((enemy.dx.powi(2) + enemy.dy.powi(2)).sqrt() - 100.0).abs() < 1e-3);
+
+    for _ in 0..200 {
+        steer(&mut enemy, &settings);
+    }
+    assert!(angle(enemy.direction()).abs() < 1e-3);
+}
+
+#[test]
+fn ships_lead_a_moving_ship() {
+    let mut settings = GameSettings::new();
+    settings.ship.vy = 200.0;
+    let enemy = enemy_ship(&settings, (150.0, 350.0), (100.0, 0.0));
+
+    let behaviour = Behaviour::choose(&enemy, &settings);
+    assert_eq!(behaviour, Behaviour::Pursue);
+    let (dx, dy) = behaviour.desired_direction(&enemy, &settings).unwrap();
+    assert!(dx > 0.0 && dy > 0.0);
+}
+
+#[test]
+fn ships_circle_around_the_ship_when_close() {
+    let settings = GameSettings::new();
+    let (ship_x, ship_y) = settings.ship.get_position();
+    let mut enemy = enemy_ship(&settings, (ship_x - 150.0, ship_y), (0.0, 100.0));
+
+    steer(&mut enemy, &settings);
+    assert_eq!(enemy.behaviour, Behaviour::Orbit);
+    // At the orbit radius the enemy keeps flying around the ship instead of towards it
+    assert!(enemy.dx.abs() < 1e-3);
+    assert!(enemy.dy > 0.0);
+}
+
+#[test]
+fn damaged_ships_flee() {
+    let settings = GameSettings::new();
+    let mut enemy = enemy_ship(&settings, (150.0, 350.0), (100.0, 0.0));
+    enemy.health = 1;
+
+    for _ in 0..200 {
+        steer(&mut enemy, &settings);
+    }
+    assert_eq!(enemy.behaviour, Behaviour::Flee);
+    assert!(enemy.dx < 0.0);
+}
+
+#[test]
+fn invisible_ships_are_not_followed() {
+    let mut settings = GameSettings::new();
+    settings.ship_visibility = 0.0;
+    let mut enemy = enemy_ship(&settings, (150.0, 350.0), (0.0, 100.0));
+
+    steer(&mut enemy, &settings);
+    assert_eq!(enemy.direction(), (0.0, 100.0));
+}
+
+#[test]
+fn asteroids_drift() {
+    let settings = GameSettings::new();
+    let mut enemy = enemy_ship(&settings, (150.0, 350.0), (0.0, 100.0));
+    enemy.enemy_type = "Asteroid".to_string();
+
+    steer(&mut enemy, &settings);
+    assert_eq!(enemy.behaviour, Behaviour::Drift);
+    assert_eq!(enemy.direction(), (0.0, 100.0));
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{projectile, quiet_game};
+use rand::SeedableRng;
+use wasm_asteroids::{
+    enemy::Enemy,
+    game::{Game, GameAction, GameRng},
+    game_settings::GameSettings,
+    positioned::{Moves, Positioned},
+    projectile::Projectile,
+    steering::{self, Behaviour},
+    wave::{Edge, Wave, WaveGroup},
+};
+
+/// Creates a swarming enemy at the given position that flies in the given direction.
+fn swarmer(id: i64, (x, y): (f32, f32), (dx, dy): (f32, f32)) -> Enemy {
+    let settings = GameSettings::new();
+    let mut enemy = Enemy::from_group(
+        &settings,
+        &WaveGroup::new(Some("Swarm"), 1),
+        0.0,
+        id,
+        &mut GameRng::seed_from_u64(0),
+    );
+    enemy.set_position(x, y);
+    enemy.set_direction(dx, dy);
+    enemy
+}
+
+/// Creates game settings in which the ship can't be seen, so swarms only flock among themselves.
+fn hidden_ship() -> GameSettings {
+    let mut settings = GameSettings::new();
+    settings.ship_visibility = 0.0;
+    settings
+}
+
+#[test]
+fn a_swarm_enters_the_field_together() {
+    let mut game = Game::new_with_seed(25);
+    let _ = game.step(GameAction::Start);
+    game.game_settings.gravity_well_interval = 0.0;
+    game.wave = Wave {
+        number: 4,
+        groups: vec![WaveGroup {
+            burst: 12,
+            edge: Edge::Left,
+            ..WaveGroup::new(Some("Swarm"), 12)
+        }],
+        spawned: 0,
+    };
+
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.enemies.len(), 12);
+    let leader = &game.enemies[0];
+    let spread = game.game_settings.swarm_perception / 2.0;
+    for enemy in &game.enemies {
+        assert_eq!(enemy.behaviour, Behaviour::Flock);
+        assert!((enemy.y - leader.y).abs() <= spread);
+        assert!(enemy.x < 0.0);
+    }
+}
+
+#[test]
+fn swarmers_keep_their_distance() {
+    let settings = hidden_ship();
+    let mut enemies = vec![
+        swarmer(1, (300.0, 300.0), (0.0, 100.0)),
+        swarmer(2, (310.0, 300.0), (0.0, 100.0)),
+    ];
+    steering::flock(&mut enemies, &settings);
+    assert!(enemies[0].dx < 0.0);
+    assert!(enemies[1].dx > 0.0);
+}
+
+#[test]
+fn swarmers_fly_towards_each_other() {
+    let settings = hidden_ship();
+    let mut enemies = vec![
+        swarmer(1, (300.0, 300.0), (0.0, 100.0)),
+        swarmer(2, (360.0, 300.0), (0.0, 100.0)),
+    ];
+    steering::flock(&mut enemies, &settings);
+    assert!(enemies[0].dx > 0.0);
+    assert!(enemies[1].dx < 0.0);
+}
+
+#[test]
+fn swarmers_are_drawn_towards_the_ship_without_speeding_up() {
+    let settings = GameSettings::new();
+    let ship = settings.ship.get_position();
+    let mut enemies = vec![swarmer(1, (ship.0 - 300.0, ship.1), (0.0, 100.0))];
+    steering::flock(&mut enemies, &settings);
+    assert!(enemies[0].dx > 0.0);
+    let speed = (enemies[0].dx.powi(2) + enemies[0].dy.powi(2)).sqrt();
+    assert!((speed - 100.0).abs() < 1e-3);
+}
+
+#[test]
+fn only_piercing_projectiles_cut_through_a_swarm() {
+    for hits in [1, 2] {
+        let mut game = quiet_game(25);
+        game.enemies = vec![
+            swarmer(1000, (300.0, 300.0), (0.0, 0.0)),
+            swarmer(1001, (304.0, 300.0), (0.0, 0.0)),
+        ];
+        game.projectiles = vec![Projectile {
+            hits,
+            ..projectile((302.0, 300.0), (0.0, 0.0))
+        }];
+
+        let _ = game.step(GameAction::Tick);
+        assert_eq!(game.enemies.len(), 2 - hits as usize);
+    }
+}
@@ -0,0 +1,58 @@
+use wasm_asteroids::{game::TICK_DURATION, game_settings::GameSettings, GameModel};
+
+fn running_model() -> GameModel {
+    let mut model = GameModel::new_with_seed(8);
+    model.start_game_with_seed(8);
+    model
+}
+
+/// Returns the number of ticks that were calculated, derived from the score.
+fn ticks(model: &GameModel) -> u32 {
+    let settings = GameSettings::new();
+    (model.get_score() / (settings.score_increase * TICK_DURATION as f64)).round() as u32
+}
+
+#[test]
+fn game_speed_does_not_depend_on_the_frame_rate() {
+    for frame_time in [4.0, 1000.0 / 60.0, 1000.0 / 144.0, 20.0, 50.0] {
+        let mut model = running_model();
+        let mut elapsed = 0.0;
+        while elapsed + frame_time <= 2000.0 {
+            model.advance(frame_time);
+            elapsed += frame_time;
+        }
+        assert!(
+            (99..=100).contains(&ticks(&model)),
+            "frame time {}",
+            frame_time
+        );
+    }
+}
+
+#[test]
+fn left_over_time_is_carried_over() {
+    let mut model = running_model();
+
+    model.advance(30.0);
+    assert_eq!(ticks(&model), 1);
+    assert!((model.get_interpolation_alpha() - 0.5).abs() < 1e-9);
+
+    model.advance(10.0);
+    assert_eq!(ticks(&model), 2);
+    assert!(model.get_interpolation_alpha().abs() < 1e-9);
+}
+
+#[test]
+fn long_gaps_are_dropped() {
+    let mut model = running_model();
+    model.advance(60_000.0);
+    assert_eq!(ticks(&model), 12);
+}
+
+#[test]
+fn paused_games_are_drawn_where_they_are() {
+    let mut model = running_model();
+    model.advance(30.0);
+    model.pause_game();
+    assert_eq!(model.get_interpolation_alpha(), 1.0);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{enemy, projectile};
+use wasm_asteroids::{
+    game::{Game, GameAction, TICK_DURATION},
+    game_settings::field::FieldTopology,
+    replay::Replay,
+};
+
+/// Creates a running game on a field with the given topology.
+fn game(topology: FieldTopology) -> Game {
+    let mut replay = Replay::new(5);
+    replay.topology = topology;
+    let mut game = replay.new_game();
+    let _ = game.step(GameAction::Start);
+    game
+}
+
+#[test]
+fn enemies_leave_a_bounded_field() {
+    let mut game = game(FieldTopology::Bounded);
+    game.enemies = vec![enemy(10.0, (1100.0, 100.0), (1500.0, 50.0))];
+
+    let _ = game.step(GameAction::Tick);
+    assert!(game.enemies.iter().all(|e| e.id != 1000));
+}
+
+#[test]
+fn enemies_wrap_around_a_toroidal_field() {
+    let mut game = game(FieldTopology::Toroidal);
+    game.enemies = vec![enemy(10.0, (1090.0, 5.0), (1500.0, -500.0))];
+
+    let _ = game.step(GameAction::Tick);
+    let enemy = game.enemies.iter().find(|e| e.id == 1000).unwrap();
+    assert!((enemy.x - 20.0).abs() < 1e-3);
+    assert!((enemy.y - 695.0).abs() < 1e-3);
+}
+
+#[test]
+fn projectiles_expire_on_a_toroidal_field() {
+    let mut game = game(FieldTopology::Toroidal);
+    let mut shot = projectile((550.0, 10.0), (250.0, 0.0));
+    // Expires in the third tick
+    shot.lifetime = 2.5 * TICK_DURATION;
+    game.projectiles = vec![shot];
+
+    let _ = game.step(GameAction::Tick);
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.projectiles.len(), 1);
+    let _ = game.step(GameAction::Tick);
+    assert!(game.projectiles.is_empty());
+}
+
+#[test]
+fn collisions_happen_across_the_edges() {
+    // The enemy is destroyed by a single hit, so it drops coins when it is hit
+    let mut fragile = enemy(10.0, (1095.0, 350.0), (50.0, 50.0));
+    fragile.health = 1;
+    fragile.armor = 0;
+
+    let mut game = game(FieldTopology::Toroidal);
+    game.enemies = vec![fragile.clone()];
+    game.projectiles = vec![projectile((5.0, 350.0), (-50.0, 0.0))];
+
+    let _ = game.step(GameAction::Tick);
+    assert!(game.projectiles.is_empty());
+    assert!(game.coins > 0);
+
+    let mut game = self::game(FieldTopology::Bounded);
+    game.enemies = vec![fragile];
+    game.projectiles = vec![projectile((5.0, 350.0), (-50.0, 0.0))];
+
+    let _ = game.step(GameAction::Tick);
+    assert_eq!(game.coins, 0);
+}
+
+#[test]
+fn replays_keep_the_topology() {
+    let mut replay = Replay::new(11);
+    replay.topology = FieldTopology::Toroidal;
+    replay.record(GameAction::Start);
+    for _ in 0..300 {
+        replay.record(GameAction::Tick);
+    }
+    let replay = Replay::from_json(&replay.to_json()).unwrap();
+
+    let game = replay.play().unwrap();
+    assert_eq!(game.game_settings.field.topology, FieldTopology::Toroidal);
+    assert!(game
+        .enemies
+        .iter()
+        .all(|e| (0.0..1100.0).contains(&e.x) && (0.0..700.0).contains(&e.y)));
+}
@@ -0,0 +1,146 @@
+use serde_json::{json, Value};
+use wasm_asteroids::{
+    game::{Game, GameAction, GameState},
+    game_ressources::GameRessource,
+    replay::{Replay, ReplayError, REPLAY_VERSION},
+    verification::{verify, VerificationError, MAX_TICKS},
+};
+
+/// Records a run in which the ship shoots in a circle and buys an item whenever possible.
+fn record(seed: u64, ticks: u32) -> (Game, Replay) {
+    let mut game = Game::new_with_seed(seed);
+    let mut replay = Replay::new(seed);
+    let mut take = |game: &mut Game, action: GameAction| {
+        replay.record(action);
+        let _ = game.step(action);
+    };
+
+    take(&mut game, GameAction::Start);
+    for tick in 0..ticks {
+        let angle = tick as f32 / 25.0;
+        take(&mut game, GameAction::RotateShip(angle.cos(), angle.sin()));
+        take(&mut game, GameAction::Shoot);
+        if tick % 200 == 0 {
+            take(&mut game, GameAction::BuyItem(3, false));
+        }
+        take(&mut game, GameAction::Tick);
+    }
+    (game, replay)
+}
+
+/// Creates the game ressource a client would submit for the game.
+fn claim(game: &Game) -> Value {
+    let items: Vec<Value> = game
+        .shop
+        .items
+        .iter()
+        .map(|item| json!({ "level": item.level, "item": { "id": item.id, "name": item.title, "description": item.description, "price": item.internal_price } }))
+        .collect();
+    json!({
+        "version": 2,
+        "id": null,
+        "ended": game.state == GameState::NotRunning,
+        "score": game.score,
+        "coins": game.coins,
+        "lives": game.lives,
+        "enemy_spawn_timeout": game.game_settings.enemy_spawn_timeout,
+        "items": items
+    })
+}
+
+fn ressource(claim: Value) -> GameRessource {
+    GameRessource::from_json(claim).unwrap()
+}
+
+#[test]
+fn accepts_honest_claims() {
+    let (game, replay) = record(21, 4000);
+
+    let report = verify(&replay, &ressource(claim(&game))).unwrap();
+    assert!(report.valid);
+}
+
+#[test]
+fn rejects_forged_score_and_coins() {
+    let (game, replay) = record(21, 2000);
+
+    let mut forged = claim(&game);
+    forged["score"] = json!(game.score - 1.0);
+    let report = verify(&replay, &ressource(forged)).unwrap();
+    assert!(!report.valid);
+    assert!(!report.score.valid);
+    assert!(report.coins.valid);
+
+    let mut forged = claim(&game);
+    forged["coins"] = json!(game.coins + 500);
+    assert!(!verify(&replay, &ressource(forged)).unwrap().coins.valid);
+}
+
+#[test]
+fn rejects_forged_items() {
+    let (game, replay) = record(21, 2000);
+
+    let mut forged = claim(&game);
+    forged["items"][0]["level"] = json!(6);
+    let report = verify(&replay, &ressource(forged)).unwrap();
+    assert!(!report.valid);
+    assert!(!report.items[0].valid);
+}
+
+#[test]
+fn rejects_free_purchases() {
+    let (_, mut replay) = record(21, 500);
+    replay.inputs.push((100, GameAction::BuyItem(0, true)));
+    replay.inputs.sort_by_key(|(tick, _)| *tick);
+
+    let game = replay.play().unwrap();
+    let report = verify(&replay, &ressource(claim(&game))).unwrap();
+    assert!(!report.valid);
+    assert_eq!(
+        report.forbidden_actions,
+        vec![(100, GameAction::BuyItem(0, true))]
+    );
+}
+
+#[test]
+fn rejects_replays_of_other_versions() {
+    let (game, replay) = record(21, 200);
+    let claim = ressource(claim(&game));
+
+    // Replays recorded before replays were versioned don't have a version
+    let mut json: Value = serde_json::from_str(&replay.to_json()).unwrap();
+    json.as_object_mut().unwrap().remove("version");
+    let unversioned = Replay::from_json(&json.to_string()).unwrap();
+    assert_eq!(
+        unversioned.play().err(),
+        Some(ReplayError::UnsupportedVersion(0))
+    );
+
+    let mut newer = replay;
+    newer.version = REPLAY_VERSION + 1;
+    assert_eq!(
+        verify(&newer, &claim).err(),
+        Some(VerificationError::Replay(ReplayError::UnsupportedVersion(
+            REPLAY_VERSION + 1
+        )))
+    );
+}
+
+#[test]
+fn rejects_implausible_replays_without_simulating_them() {
+    let (game, mut replay) = record(21, 200);
+
+    // The score can't rise faster than by the score increase of every tick
+    let mut forged = claim(&game);
+    forged["score"] = json!(game.score + 100.0);
+    assert_eq!(
+        verify(&replay, &ressource(forged)).err(),
+        Some(VerificationError::ImplausibleScore)
+    );
+
+    replay.ticks = MAX_TICKS + 1;
+    assert_eq!(
+        verify(&replay, &ressource(claim(&game))).err(),
+        Some(VerificationError::TooLong(MAX_TICKS + 1))
+    );
+}
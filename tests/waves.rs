@@ -0,0 +1,154 @@
+use rand::SeedableRng;
+use wasm_asteroids::{
+    archetype::{Archetype, CatalogError},
+    enemy::Enemy,
+    game::{Game, GameAction, GameRng, GameState, TICKS_PER_SECOND},
+    game_event::GameEvent,
+    game_settings::GameSettings,
+    wave::{Edge, Wave, WaveGroup},
+};
+
+/// Creates a running game without gravity wells.
+fn game() -> Game {
+    let mut game = Game::new_with_seed(21);
+    let _ = game.step(GameAction::Start);
+    game.game_settings.gravity_well_interval = 0.0;
+    game
+}
+
+/// Creates a running game whose current wave was completely spawned and destroyed.
+fn cleared_game() -> Game {
+    let mut game = game();
+    game.wave.spawned = game.wave.total();
+    let _ = game.step(GameAction::Tick);
+    game
+}
+
+#[test]
+fn waves_spawn_their_groups_in_bursts() {
+    let mut wave = Wave::new(3);
+    assert_eq!(wave.total(), 15);
+
+    let (group, count) = wave.next_burst().unwrap();
+    assert_eq!((group.edge, count), (Edge::Left, 2));
+    for _ in 0..2 {
+        wave.next_burst();
+    }
+    let (group, _) = wave.next_burst().unwrap();
+    assert_eq!(group.edge, Edge::Right);
+
+    while wave.next_burst().is_some() {}
+    assert!(wave.is_spawned());
+    assert_eq!(wave.progress(), 1.0);
+}
+
+#[test]
+fn waves_only_spawn_archetypes_of_the_catalog() {
+    let catalog = Archetype::catalog();
+    assert_eq!(Wave::validate_waves(Wave::authored(), catalog), Ok(()));
+
+    let renamed: Vec<Archetype> = catalog
+        .iter()
+        .cloned()
+        .map(|mut archetype| {
+            if archetype.name == "Swarm" {
+                archetype.name = "Flock".to_string();
+            }
+            archetype
+        })
+        .collect();
+    assert_eq!(
+        Wave::validate_waves(Wave::authored(), &renamed),
+        Err(CatalogError::UnknownWaveArchetype("Swarm".to_string()))
+    );
+}
+
+#[test]
+fn later_waves_are_larger() {
+    assert!(Wave::new(8).total() > Wave::new(6).total());
+    assert!(Wave::new(6).total() > Wave::new(5).total());
+}
+
+#[test]
+fn enemies_enter_from_the_edge_of_their_group() {
+    let settings = GameSettings::new();
+    let group = WaveGroup::new(Some("Ship"), 1);
+    let mut rng = GameRng::seed_from_u64(0);
+    for edge in [Edge::Top, Edge::Bottom, Edge::Left, Edge::Right] {
+        let group = WaveGroup {
+            edge,
+            ..group.clone()
+        };
+        let enemy = Enemy::from_group(&settings, &group, 0.0, 1000, &mut rng);
+        assert_eq!(enemy.enemy_type, "Ship");
+        let outside = match edge {
+            Edge::Top => enemy.y > settings.field.height as f32,
+            Edge::Bottom => enemy.y < 0.0,
+            Edge::Left => enemy.x < 0.0,
+            _ => enemy.x > settings.field.width as f32,
+        };
+        assert!(outside);
+    }
+}
+
+#[test]
+fn a_wave_stops_spawning_once_all_its_enemies_spawned() {
+    let mut game = game();
+    let total = game.wave.total() as usize;
+    let mut spawned = 0;
+    while game.state == GameState::Running {
+        // Destroy every enemy right away, so the wave can be cleared
+        game.time_until_enemy_spawn = 0.0;
+        game.enemies.clear();
+        let _ = game.step(GameAction::Tick);
+        spawned += game.enemies.len();
+    }
+    assert_eq!(spawned, total);
+    assert_eq!(game.state, GameState::Intermission);
+}
+
+#[test]
+fn clearing_a_wave_starts_the_intermission() {
+    let mut game = cleared_game();
+    assert_eq!(game.state, GameState::Intermission);
+    assert!(game
+        .take_events()
+        .contains(&GameEvent::WaveCleared { number: 1 }));
+
+    // Shopping is possible during the intermission, shooting is not
+    game.coins = 10_000;
+    assert!(game.step(GameAction::BuyItem(0, false)).is_ok());
+    let _ = game.step(GameAction::Shoot);
+    assert!(game.projectiles.is_empty());
+
+    let ticks = (game.game_settings.intermission_time * TICKS_PER_SECOND as f32) as usize;
+    for _ in 0..ticks + 1 {
+        let _ = game.step(GameAction::Tick);
+    }
+    assert_eq!(game.state, GameState::Running);
+    assert_eq!(game.wave.number, 2);
+    assert!(game
+        .take_events()
+        .contains(&GameEvent::WaveStarted { number: 2 }));
+}
+
+#[test]
+fn the_next_wave_can_be_started_early() {
+    let mut game = cleared_game();
+    let _ = game.step(GameAction::Pause);
+    assert_eq!(game.state, GameState::Intermission);
+
+    let _ = game.step(GameAction::Start);
+    assert_eq!(game.state, GameState::Running);
+    assert_eq!(game.wave.number, 2);
+    assert_eq!(game.wave.spawned, 0);
+}
+
+#[test]
+fn the_wave_survives_a_save() {
+    let game = cleared_game();
+    let restored = Game::from_snapshot(game.snapshot());
+    assert_eq!(restored.state, GameState::Intermission);
+    assert_eq!(restored.wave, game.wave);
+    assert_eq!(restored.time_until_next_wave, game.time_until_next_wave);
+}
@@ -7,6 +7,9 @@ export const coinsEl = document.getElementById("coins")!;
 export const livesEl = document.getElementById("lives")!;
 export const shipContainer = document.getElementById("ship-container")!;
 export const shootRefillEl = document.getElementById("shoot-refill")!;
+export const bossHealthEl = document.getElementById("boss-health")!;
+export const waveEl = document.getElementById("wave")!;
+export const effectsEl = document.getElementById("effects")!;
 export const hotkeysEl = document.getElementById("hotkeys")!;
 export const gameOverEl = document.getElementById("game-over")!;
 export const shopCloseBtn = document.getElementById("shop-close")!;
@@ -24,6 +24,15 @@ const hotkeys = [
             model.toggleGameState();
         }
     },
+    {
+        key: "W",
+        description: "Thrust",
+        predicate: key("w"),
+        callback: (event: any) => {
+            event.preventDefault();
+            model.thrust(true);
+        }
+    },
     {
         key: "Space",
         description: "Shoot",
@@ -37,10 +46,15 @@ const hotkeys = [
 
 model.registerHotkeys(hotkeys);
 
-const gameLoopIntervall = setInterval(() => {
-    model.tick();
+// The game is calculated in fixed ticks, so it runs at the same speed no matter how often the browser draws a frame
+let lastFrame = performance.now();
+let gameLoopFrame = requestAnimationFrame(function gameLoop(now: number) {
+    model.advance(now - lastFrame);
+    lastFrame = now;
+    view.renderEvents(model.takeEvents());
     view.render();
-}, 20);
+    gameLoopFrame = requestAnimationFrame(gameLoop);
+});
 
 startBtn.addEventListener("click", () => {
     model.toggleGameState();
@@ -59,8 +73,9 @@ shopCloseBtn.addEventListener("click", () => {
 
 window.addEventListener("mousemove", (e) => {
     const rect = field.getBoundingClientRect();
+    // The field points up, the direction of the ship points down the screen
     const x = (e.clientX - rect.left) - model.ship.x;
-    const y = (e.clientY - rect.top) - model.ship.y;
+    const y = (e.clientY - rect.top) - (rect.height - model.ship.y);
 
     model.rotateShip(x, y);
     view.render();
@@ -79,6 +94,12 @@ window.addEventListener("keydown", (e) => {
     model.handleHotkey(e)
 })
 
+window.addEventListener("keyup", (e) => {
+    if (e.key === "w") {
+        model.thrust(false);
+    }
+})
+
 enum LoginScreenAction {
     Login,
     Register
@@ -89,7 +110,7 @@ endGameBtn.addEventListener("click", () => {
 })
 
 window.onbeforeunload = (e) => {
-    clearInterval(gameLoopIntervall);
+    cancelAnimationFrame(gameLoopFrame);
     if (model.gameState !== GameState.NotRunning)
         e.preventDefault()
 }
@@ -1,6 +1,10 @@
+import { Boss } from "../bindings/Boss";
+import { Effect } from "../bindings/Effect";
+import { GameEvent } from "../bindings/GameEvent";
 import { GameRessource } from "../bindings/GameRessource";
 import { ItemLevelRessource } from "../bindings/ItemLevelRessource";
-import { Enemy, Field, GameModel, GameState, Item, Projectile, Ship } from "../pkg";
+import { PowerUp } from "../bindings/PowerUp";
+import { Enemy, Field, GameModel, GameState, GravityWell, Item, Projectile, Ship } from "../pkg";
 import HotkeyHandler, { Hotkey } from "./hotkeys";
 import ViewModel from "./view_model";
 
@@ -12,6 +16,7 @@ export default class Model {
     private viewModel: ViewModel;
     private hotkeyModel: HotkeyHandler;
     private lastFrameGameOver: boolean = false;
+    private thrusting: boolean = false;
     private gameOverCallbacks: (() => void)[] = [];
 
 
@@ -37,6 +42,14 @@ export default class Model {
         this.gameModel.tick();
     }
 
+    advance(elapsedMs: number): void {
+        this.gameModel.advance(elapsedMs);
+    }
+
+    takeEvents(): GameEvent[] {
+        return this.gameModel.take_events();
+    }
+
     async startGame() {
         this.gameModel.start_game();
     }
@@ -44,7 +57,7 @@ export default class Model {
     toggleGameState(): void {
         if (this.gameState === GameState.NotRunning) {
             this.startGame();
-        } else if (this.gameState === GameState.Paused) {
+        } else if (this.gameState === GameState.Paused || this.gameState === GameState.Intermission) {
             this.resumeGame();
         } else {
             this.pauseGame();
@@ -59,6 +72,13 @@ export default class Model {
         this.gameModel.rotate_ship(dx, dy);
     }
 
+    // Key repeats would record the same action over and over, so only changes are passed on
+    thrust(thrusting: boolean): void {
+        if (this.thrusting === thrusting) return;
+        this.thrusting = thrusting;
+        this.gameModel.thrust(thrusting);
+    }
+
     pauseGame(): void {
         this.gameModel.pause_game();
     }
@@ -73,6 +93,10 @@ export default class Model {
 
     toggleShop(): void {
         this.viewModel.shopIsOpen = !this.viewModel.shopIsOpen;
+        // The intermission is a break of its own, so opening the shop doesn't pause it
+        if (this.gameState === GameState.Intermission) {
+            return;
+        }
         if (this.shopOpen) {
             this.pauseGame();
         } else if (this.gameState === GameState.Paused) {
@@ -127,6 +151,58 @@ export default class Model {
         return this.gameModel.get_projectiles();
     }
 
+    get interpolationAlpha(): number {
+        return this.gameModel.get_interpolation_alpha();
+    }
+
+    get tickDuration(): number {
+        return this.gameModel.get_tick_duration();
+    }
+
+    get gravityWells(): GravityWell[] {
+        return this.gameModel.get_gravity_wells();
+    }
+
+    get powerUps(): PowerUp[] {
+        return this.gameModel.get_power_ups();
+    }
+
+    get effects(): Effect[] {
+        return this.gameModel.get_effects();
+    }
+
+    get shielded(): boolean {
+        return this.gameModel.is_shielded();
+    }
+
+    get waveNumber(): number {
+        return this.gameModel.get_wave_number();
+    }
+
+    get waveProgress(): number {
+        return this.gameModel.get_wave_progress();
+    }
+
+    get waveEnemiesLeft(): number {
+        return this.gameModel.get_wave_enemies_left();
+    }
+
+    get timeUntilNextWave(): number {
+        return this.gameModel.get_time_until_next_wave();
+    }
+
+    get boss(): Boss | undefined {
+        return this.gameModel.get_boss();
+    }
+
+    get bossPhase(): number | undefined {
+        return this.gameModel.get_boss_phase();
+    }
+
+    get bossHealthFraction(): number | undefined {
+        return this.gameModel.get_boss_health_fraction();
+    }
+
     get field(): Field {
         return this.gameModel.get_field();
     }
@@ -172,6 +248,7 @@ export default class Model {
             }
         })
         return {
+            version: this.gameModel.get_save_version(),
             id: this.gameModel.get_id() ?? null,
             enemy_spawn_timeout: this.gameModel.get_enemy_spawn_timeout(),
             ended: this.isGameOver,
@@ -179,6 +256,7 @@ export default class Model {
             coins: this.coins,
             lives: this.lives,
             items: itemLevels,
+            snapshot: this.gameModel.get_snapshot(),
         }
     }
 
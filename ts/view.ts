@@ -1,12 +1,26 @@
 import {
     coinsEl, field, scoreEl, ship, startBtn, livesEl, shipContainer, shootRefillEl, hotkeysEl,
-    gameOverEl, shopEl, shopItemContainer, shopItemTemplate, errorEl, loadingContainer, pauseIcon, playIcon
+    gameOverEl, shopEl, shopItemContainer, shopItemTemplate, errorEl, loadingContainer, pauseIcon, playIcon,
+    bossHealthEl, waveEl, effectsEl
 } from "./elements";
+import { Boss } from "../bindings/Boss";
+import { Effect } from "../bindings/Effect";
+import { PowerUp } from "../bindings/PowerUp";
+import { PowerUpKind } from "../bindings/PowerUpKind";
+import { GameEvent } from "../bindings/GameEvent";
 import Model, {
-    Enemy, Projectile, GameState, Ship, Item
+    Enemy, GravityWell, Projectile, GameState, Ship, Item
 } from "./model";
 
 
+const POWER_UP_LABELS: Record<PowerUpKind, string> = {
+    RapidFire: "Rapid fire",
+    TripleShot: "Triple shot",
+    Shield: "Shield",
+    ExtraLife: "Extra life",
+    CoinBurst: "Coin burst",
+};
+
 export default class View {
     private model: Model;
     private lastShopItems: Item[] = [];
@@ -27,7 +41,8 @@ export default class View {
         this.displayIf(this.model.errorMessage !== undefined, errorEl);
         this.renderError();
 
-        if (this.model.shopOpen) {
+        // The shop is the focus of the intermission
+        if (this.model.shopOpen || this.model.gameState === GameState.Intermission) {
             shopEl.classList.add("open");
             this.renderShopItems();
         } else {
@@ -36,6 +51,12 @@ export default class View {
 
         this.renderShootRefillBar(this.model.shootRefillPercentage);
 
+        this.renderBossHealthBar(this.model.bossHealthFraction, this.model.bossPhase);
+
+        this.renderWave(this.model.gameState);
+
+        this.renderEffects(this.model.effects);
+
         field.style.width = this.model.field.width + "px";
         field.style.height = this.model.field.height + "px";
 
@@ -45,6 +66,7 @@ export default class View {
         this.oldLives = this.model.lives;
 
         this.renderShip(this.model.ship, this.model.invulnerable);
+        ship.classList.toggle("shielded", this.model.shielded);
 
         scoreEl.innerText = Math.round(this.model.score).toString();
         coinsEl.querySelector("span")!.innerText = this.model.coins.toString();
@@ -55,8 +77,23 @@ export default class View {
 
         this.removeElements(document.querySelectorAll(".projectile"));
 
+        this.removeElements(document.querySelectorAll(".gravity-well"));
+
+        this.removeElements(document.querySelectorAll(".boss"));
+
+        this.removeElements(document.querySelectorAll(".power-up"));
+
+        this.model.gravityWells.forEach(this.renderGravityWell.bind(this));
+
         this.model.enemies.forEach(this.renderEnemy.bind(this));
 
+        this.model.powerUps.forEach(this.renderPowerUp.bind(this));
+
+        const boss = this.model.boss;
+        if (boss !== undefined) {
+            this.renderBoss(boss);
+        }
+
         this.model.projectiles.forEach(this.renderProjectile.bind(this));
     }
 
@@ -180,15 +217,46 @@ export default class View {
         shootRefillEl.querySelector("div")!.style.width = percentage + "%";
     }
 
+    // During the intermission the countdown to the next wave is shown instead of the progress of the wave
+    private renderWave(gameState: GameState) {
+        const intermission = gameState === GameState.Intermission;
+        waveEl.classList.toggle("intermission", intermission);
+        waveEl.querySelector("span")!.innerText = intermission
+            ? `Wave ${this.model.waveNumber + 1} in ${Math.ceil(this.model.timeUntilNextWave)}s`
+            : `Wave ${this.model.waveNumber} · ${this.model.waveEnemiesLeft} left`;
+        waveEl.querySelector("div")!.style.width = (intermission ? 0 : this.model.waveProgress * 100) + "%";
+    }
+
+    // Lists the active effects of power-ups with the seconds they have left
+    private renderEffects(effects: Effect[]) {
+        effectsEl.innerText = effects
+            .map(effect => `${POWER_UP_LABELS[effect.kind]} ${Math.ceil(effect.time_left)}s`)
+            .join(" · ");
+    }
+
+    // The bar is only shown while a boss is on the field. It blinks in the last phase.
+    private renderBossHealthBar(fraction: number | undefined, phase: number | undefined) {
+        this.displayIf(fraction !== undefined, bossHealthEl);
+        bossHealthEl.querySelector("div")!.style.width = (fraction ?? 0) * 100 + "%";
+        bossHealthEl.classList.toggle("phase-3", phase === 3);
+    }
+
     private removeElements(elements: NodeListOf<HTMLElement>) {
         elements.forEach((element) => {
             element.remove();
         })
     }
 
+    // Objects are drawn between their position in the last tick and the current one, so they move smoothly when the screen refreshes faster than the game ticks
+    private interpolate(x: number, y: number, dx: number, dy: number): [number, number] {
+        const behind = this.model.tickDuration * (1 - this.model.interpolationAlpha);
+        return [x - dx * behind, y - dy * behind];
+    }
+
     private renderShip(shipInfo: Ship, isInvulnerable: boolean) {
-        shipContainer.style.left = shipInfo.x - shipInfo.width / 2 + "px";
-        shipContainer.style.bottom = shipInfo.y - shipInfo.height / 2 + "px";
+        const [x, y] = this.interpolate(shipInfo.x, shipInfo.y, shipInfo.vx, shipInfo.vy);
+        shipContainer.style.left = x - shipInfo.width / 2 + "px";
+        shipContainer.style.bottom = y - shipInfo.height / 2 + "px";
         ship.style.width = shipInfo.width + "px";
         ship.style.height = shipInfo.height + "px";
         ship.style.transform = `rotate(${shipInfo.get_angle() + 0.8}rad)`;
@@ -214,6 +282,9 @@ export default class View {
             case GameState.NotRunning:
                 textEl.innerText = "Start new game";
                 break;
+            case GameState.Intermission:
+                textEl.innerText = "Start next wave";
+                break;
 
             default:
                 break;
@@ -226,24 +297,133 @@ export default class View {
         const projectileElement = document.createElement("div");
         projectileElement.id = `projectile-${projectile.id}`;
         projectileElement.className = "projectile";
+        // Hostile projectiles are drawn differently from the ones of the player
+        projectileElement.classList.add(projectile.faction.toString().toLowerCase());
         field.appendChild(projectileElement);
 
 
         const angle = Math.atan2(projectile.dy, projectile.dx)
 
-        this.placeElement(projectileElement, projectile.x, projectile.y, projectile.radius, projectile.radius);
+        const [x, y] = this.interpolate(projectile.x, projectile.y, projectile.dx, projectile.dy);
+        this.placeElement(projectileElement, x, y, projectile.radius, projectile.radius);
         projectileElement.style.transform = `rotate(${angle}rad)`;
     }
 
+    // Plays the effects of the events that happened since the last frame
+    renderEvents(events: GameEvent[]) {
+        events.forEach(event => {
+            switch (event.type) {
+                case "EnemyDestroyed":
+                case "EnemySplit":
+                case "EnemySwallowed":
+                    this.renderExplosion(event.x, event.y);
+                    break;
+                case "BossDefeated":
+                    this.renderExplosion(event.x, event.y, 160);
+                    break;
+                case "EnemyDamaged":
+                    this.renderExplosion(event.x, event.y, 16);
+                    break;
+
+                default:
+                    break;
+            }
+        });
+    }
+
+    // Power-ups blink shortly before they disappear
+    private renderPowerUp(powerUp: PowerUp) {
+        const powerUpElement = document.createElement("div");
+        powerUpElement.id = `power-up-${powerUp.id}`;
+        powerUpElement.className = "power-up";
+        powerUpElement.classList.add(powerUp.kind.toLowerCase());
+        powerUpElement.classList.toggle("expiring", powerUp.lifetime < 2);
+        powerUpElement.innerText = POWER_UP_LABELS[powerUp.kind].charAt(0);
+        field.appendChild(powerUpElement);
+
+        const [x, y] = this.interpolate(powerUp.x, powerUp.y, powerUp.dx, powerUp.dy);
+        this.placeElement(powerUpElement, x, y, powerUp.radius * 2, powerUp.radius * 2);
+    }
+
+    // The explosion removes itself once its animation is over
+    private renderExplosion(x: number, y: number, size: number = 40) {
+        const explosion = document.createElement("div");
+        explosion.className = "explosion";
+        field.appendChild(explosion);
+
+        this.placeElement(explosion, x, y, size, size);
+        explosion.addEventListener("animationend", () => explosion.remove());
+    }
+
+    // The well is drawn larger than its event horizon, so the pull around it can be seen
+    private renderGravityWell(well: GravityWell) {
+        const wellElement = document.createElement("div");
+        wellElement.id = `gravity-well-${well.id}`;
+        wellElement.className = "gravity-well";
+        field.appendChild(wellElement);
+
+        this.placeElement(wellElement, well.x, well.y, well.horizon * 6, well.horizon * 6);
+    }
+
+    // Draws the outline the enemy collides with. Enemies without an outline are round.
+    // The outline fades as the enemy loses health and is solid while the enemy has armor.
     private renderEnemy(enemy: Enemy) {
 
         const enemyElement = document.createElement("div");
         enemyElement.id = `enemy-${enemy.id}`;
         enemyElement.className = "enemy";
         enemyElement.classList.add(enemy.enemy_type.toString().toLowerCase());
+        enemyElement.classList.toggle("armored", enemy.armor > 0);
+        enemyElement.style.setProperty("--health", `${Math.max(enemy.health, 0) / Math.max(enemy.max_health, 1)}`);
         field.appendChild(enemyElement);
 
-        this.placeElement(enemyElement, enemy.x, enemy.y, enemy.radius, enemy.radius);
+        const outline: [number, number][] = enemy.outline;
+        const svgNamespace = "http://www.w3.org/2000/svg";
+        const svg = document.createElementNS(svgNamespace, "svg");
+        svg.setAttribute("viewBox", "-1 -1 2 2");
+        const shape = outline.length > 0
+            ? document.createElementNS(svgNamespace, "polygon")
+            : document.createElementNS(svgNamespace, "circle");
+        if (outline.length > 0) {
+            // The field points up, svg points down
+            shape.setAttribute("points", outline.map(([x, y]) => `${x},${-y}`).join(" "));
+        } else {
+            shape.setAttribute("r", "1");
+        }
+        shape.setAttribute("vector-effect", "non-scaling-stroke");
+        svg.appendChild(shape);
+        enemyElement.appendChild(svg);
+
+        const [x, y] = this.interpolate(enemy.x, enemy.y, enemy.dx, enemy.dy);
+        this.placeElement(enemyElement, x, y, enemy.radius * 2, enemy.radius * 2);
+    }
+
+    // Draws the octagon of the boss and its weak points, relative to the center of the boss
+    private renderBoss(boss: Boss) {
+        const bossElement = document.createElement("div");
+        bossElement.id = `boss-${boss.id}`;
+        bossElement.className = "boss";
+        field.appendChild(bossElement);
+
+        const svgNamespace = "http://www.w3.org/2000/svg";
+        const svg = document.createElementNS(svgNamespace, "svg");
+        svg.setAttribute("viewBox", `${-boss.radius} ${-boss.radius} ${boss.radius * 2} ${boss.radius * 2}`);
+        const body = document.createElementNS(svgNamespace, "polygon");
+        const corners = Array.from({ length: 8 }, (_, i) => boss.angle + i / 8 * 2 * Math.PI);
+        // The field points up, svg points down
+        body.setAttribute("points", corners.map(a => `${Math.cos(a) * boss.radius},${-Math.sin(a) * boss.radius}`).join(" "));
+        svg.appendChild(body);
+        boss.weak_points.forEach(point => {
+            const circle = document.createElementNS(svgNamespace, "circle");
+            circle.setAttribute("cx", (point.x - boss.x).toString());
+            circle.setAttribute("cy", (boss.y - point.y).toString());
+            circle.setAttribute("r", point.radius.toString());
+            svg.appendChild(circle);
+        });
+        bossElement.appendChild(svg);
+
+        const [x, y] = this.interpolate(boss.x, boss.y, boss.dx, boss.dy);
+        this.placeElement(bossElement, x, y, boss.radius * 2, boss.radius * 2);
     }
 
     // For each live render a heart. All lost lives are grey hearts, the rest are red.